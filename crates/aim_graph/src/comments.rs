@@ -0,0 +1,61 @@
+pub const MAX_COMMENT_LENGTH: usize = 10_000;
+
+/// What deleting a comment does to it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CommentDeletion {
+    /// Nobody replied, the comment is removed together with its link.
+    Remove,
+    /// The comment becomes a tombstone without text, so its replies keep their
+    /// place in the thread.
+    Tombstone,
+}
+
+pub fn comment_deletion(reply_count: usize) -> CommentDeletion {
+    if reply_count == 0 {
+        CommentDeletion::Remove
+    } else {
+        CommentDeletion::Tombstone
+    }
+}
+
+/// Comments need some text, tombstones must not have any.
+pub fn validate_comment_text(text: &str, deleted: bool) -> Result<(), String> {
+    if deleted {
+        return if text.is_empty() {
+            Ok(())
+        } else {
+            Err("a deleted comment must not have any text".into())
+        };
+    }
+    if text.trim().is_empty() {
+        return Err("a comment must not be empty".into());
+    }
+    if text.chars().count() > MAX_COMMENT_LENGTH {
+        return Err(format!("a comment must not be longer than {} characters", MAX_COMMENT_LENGTH));
+    }
+    Ok(())
+}
+
+/// A tombstone stays one: it can neither be edited nor brought back.
+pub fn validate_comment_edit(was_deleted: bool, text: &str, deleted: bool) -> Result<(), String> {
+    if was_deleted {
+        return Err("a deleted comment cannot be changed".into());
+    }
+    validate_comment_text(text, deleted)
+}
+
+/// A comment together with its replies.
+pub trait Thread: Sized {
+    fn is_deleted(&self) -> bool;
+    fn replies(&self) -> &[Self];
+    fn replies_mut(&mut self) -> &mut Vec<Self>;
+}
+
+/// Leaves out tombstones that have no replies left, once the replies below them
+/// were deleted or pruned themselves.
+pub fn prune_deleted<T: Thread>(threads: &mut Vec<T>) {
+    for thread in threads.iter_mut() {
+        prune_deleted(thread.replies_mut());
+    }
+    threads.retain(|thread| !thread.is_deleted() || !thread.replies().is_empty());
+}
@@ -0,0 +1,363 @@
+use serde::{
+    de,
+    Deserialize,
+    Deserializer,
+};
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    iter::Sum,
+    ops::Add,
+    str::FromStr,
+};
+
+/// Efforts are serialized as `{"Days": 3}`, but callers may also send the short
+/// string form produced by `to_string`, like `"3d"` or `"1w 2d"`.
+#[derive(Serialize, Debug, Clone)]
+pub enum Effort {
+    Minutes(u64),
+    Hours(u64),
+    Days(u64),
+    Weeks(u64),
+    Months(u64),
+    Years(u64),
+}
+
+impl fmt::Display for Effort {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Effort::Minutes(m) => write!(f, "{}min", m),
+            Effort::Hours(h) => write!(f, "{}h", h),
+            Effort::Days(d) => write!(f, "{}d", d),
+            Effort::Weeks(w) => write!(f, "{}w", w),
+            Effort::Months(m) => write!(f, "{}m", m),
+            Effort::Years(y) => write!(f, "{}y", y)
+        }
+    }
+}
+
+/// Builds an effort of one unit from an amount.
+type EffortUnit = fn(u64) -> Effort;
+
+/// How much working time the calendar units stand for. The defaults can be
+/// overridden through the `working_hours_per_day` and `working_days_per_week`
+/// DNA properties.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EffortScale {
+    pub working_hours_per_day: u64,
+    pub working_days_per_week: u64,
+}
+
+impl Default for EffortScale {
+    fn default() -> EffortScale {
+        EffortScale {
+            working_hours_per_day: 8,
+            working_days_per_week: 5,
+        }
+    }
+}
+
+/// The unit sizes saturate, so an absurd scale from the DNA properties cannot
+/// overflow them.
+impl EffortScale {
+    fn minutes_per_day(&self) -> u64 {
+        self.working_hours_per_day.saturating_mul(60)
+    }
+
+    fn minutes_per_week(&self) -> u64 {
+        self.minutes_per_day().saturating_mul(self.working_days_per_week)
+    }
+
+    /// A year has 52 working weeks and a month is a twelfth of it.
+    fn minutes_per_month(&self) -> u64 {
+        self.minutes_per_week().saturating_mul(52) / 12
+    }
+
+    fn minutes_per_year(&self) -> u64 {
+        self.minutes_per_month().saturating_mul(12)
+    }
+}
+
+impl Effort {
+    /// The working time of this effort in minutes, or `None` if it does not fit
+    /// into a `u64`.
+    pub fn checked_minutes(&self, scale: &EffortScale) -> Option<u64> {
+        match self {
+            Effort::Minutes(m) => Some(*m),
+            Effort::Hours(h) => h.checked_mul(60),
+            Effort::Days(d) => d.checked_mul(scale.minutes_per_day()),
+            Effort::Weeks(w) => w.checked_mul(scale.minutes_per_week()),
+            Effort::Months(m) => m.checked_mul(scale.minutes_per_month()),
+            Effort::Years(y) => y.checked_mul(scale.minutes_per_year()),
+        }
+    }
+
+    /// The working time of this effort in minutes, saturating at `u64::MAX`.
+    pub fn to_minutes(&self, scale: &EffortScale) -> u64 {
+        self.checked_minutes(scale).unwrap_or(u64::MAX)
+    }
+
+    /// Expresses an amount of working minutes in the largest unit that keeps it whole.
+    pub fn from_minutes(minutes: u64, scale: &EffortScale) -> Effort {
+        let units: [(u64, EffortUnit); 5] = [
+            (scale.minutes_per_year(), Effort::Years),
+            (scale.minutes_per_month(), Effort::Months),
+            (scale.minutes_per_week(), Effort::Weeks),
+            (scale.minutes_per_day(), Effort::Days),
+            (60, Effort::Hours),
+        ];
+        for (unit_minutes, effort) in units.iter() {
+            if minutes > 0 && minutes.checked_rem(*unit_minutes) == Some(0) {
+                return effort(minutes / unit_minutes);
+            }
+        }
+        Effort::Minutes(minutes)
+    }
+
+    pub fn normalized(&self, scale: &EffortScale) -> Effort {
+        Effort::from_minutes(self.to_minutes(scale), scale)
+    }
+
+    pub fn add_with(&self, other: &Effort, scale: &EffortScale) -> Effort {
+        Effort::from_minutes(self.to_minutes(scale).saturating_add(other.to_minutes(scale)), scale)
+    }
+
+    pub fn zero() -> Effort {
+        Effort::Minutes(0)
+    }
+}
+
+/// Comparing and adding efforts uses the default `EffortScale`; use `to_minutes`
+/// and `add_with` where the DNA's scale matters.
+impl PartialEq for Effort {
+    fn eq(&self, other: &Effort) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Effort {}
+
+impl PartialOrd for Effort {
+    fn partial_cmp(&self, other: &Effort) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Effort {
+    fn cmp(&self, other: &Effort) -> Ordering {
+        let scale = EffortScale::default();
+        self.to_minutes(&scale).cmp(&other.to_minutes(&scale))
+    }
+}
+
+impl Add for Effort {
+    type Output = Effort;
+
+    fn add(self, other: Effort) -> Effort {
+        self.add_with(&other, &EffortScale::default())
+    }
+}
+
+impl Sum for Effort {
+    fn sum<I: Iterator<Item = Effort>>(iter: I) -> Effort {
+        iter.fold(Effort::zero(), Add::add)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+enum Unit {
+    Minutes,
+    Hours,
+    Days,
+    Weeks,
+    Months,
+    Years,
+}
+
+impl Unit {
+    fn from_suffix(suffix: &str) -> Option<Unit> {
+        match suffix {
+            "min" => Some(Unit::Minutes),
+            "h" => Some(Unit::Hours),
+            "d" => Some(Unit::Days),
+            "w" => Some(Unit::Weeks),
+            "m" => Some(Unit::Months),
+            "y" => Some(Unit::Years),
+            _ => None
+        }
+    }
+
+    /// Working time of the unit, so compound inputs add up like `Effort`s do.
+    fn minutes(self, scale: &EffortScale) -> f64 {
+        (match self {
+            Unit::Minutes => 1,
+            Unit::Hours => 60,
+            Unit::Days => scale.minutes_per_day(),
+            Unit::Weeks => scale.minutes_per_week(),
+            Unit::Months => scale.minutes_per_month(),
+            Unit::Years => scale.minutes_per_year(),
+        }) as f64
+    }
+
+    fn smaller(self) -> Unit {
+        match self {
+            Unit::Minutes | Unit::Hours => Unit::Minutes,
+            Unit::Days => Unit::Hours,
+            Unit::Weeks | Unit::Months => Unit::Days,
+            Unit::Years => Unit::Months,
+        }
+    }
+
+    fn effort(self, value: u64) -> Effort {
+        match self {
+            Unit::Minutes => Effort::Minutes(value),
+            Unit::Hours => Effort::Hours(value),
+            Unit::Days => Effort::Days(value),
+            Unit::Weeks => Effort::Weeks(value),
+            Unit::Months => Effort::Months(value),
+            Unit::Years => Effort::Years(value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseEffortError {
+    Empty,
+    InvalidNumber(String),
+    MissingUnit(String),
+    UnknownUnit(String),
+    TooLarge(String),
+}
+
+impl fmt::Display for ParseEffortError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseEffortError::Empty => write!(f, "effort is empty"),
+            ParseEffortError::InvalidNumber(number) => write!(f, "invalid effort amount '{}'", number),
+            ParseEffortError::MissingUnit(number) => write!(f, "effort amount '{}' has no unit", number),
+            ParseEffortError::UnknownUnit(unit) => write!(f, "unknown effort unit '{}', expected min, h, d, w, m or y", unit),
+            ParseEffortError::TooLarge(effort) => write!(f, "effort '{}' is too large", effort),
+        }
+    }
+}
+
+impl std::error::Error for ParseEffortError {}
+
+impl FromStr for Effort {
+    type Err = ParseEffortError;
+
+    /// Parses with the default `EffortScale`, see `Effort::parse_with`.
+    fn from_str(s: &str) -> Result<Effort, ParseEffortError> {
+        Effort::parse_with(s, &EffortScale::default())
+    }
+}
+
+impl Effort {
+    /// Parses efforts like "3h", "10min", "1.5h" or "1w 2d". The parts of compound
+    /// and fractional inputs are combined as working time of the given scale and
+    /// expressed in the largest unit, no larger than the smallest one given, that
+    /// keeps the amount whole. With the default scale "1w 2d" becomes `Days(7)`
+    /// and "1.5d" becomes `Hours(12)`.
+    pub fn parse_with(s: &str, scale: &EffortScale) -> Result<Effort, ParseEffortError> {
+        let mut chars = s.chars().peekable();
+        let mut total_minutes = 0.0;
+        let mut smallest_unit: Option<Unit> = None;
+
+        loop {
+            while let Some(&c) = chars.peek() {
+                if !c.is_whitespace() {
+                    break;
+                }
+                chars.next();
+            }
+            if chars.peek().is_none() {
+                break;
+            }
+            let mut number = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_ascii_digit() || c == '.' {
+                    number.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            while let Some(&c) = chars.peek() {
+                if !c.is_whitespace() {
+                    break;
+                }
+                chars.next();
+            }
+            let mut suffix = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_alphabetic() {
+                    suffix.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+
+            let amount = number.parse::<f64>()
+                .map_err(|_| ParseEffortError::InvalidNumber(number.clone() + &suffix))?;
+            if suffix.is_empty() {
+                return Err(ParseEffortError::MissingUnit(number));
+            }
+            let unit = Unit::from_suffix(&suffix.to_lowercase())
+                .ok_or(ParseEffortError::UnknownUnit(suffix))?;
+
+            total_minutes += amount * unit.minutes(scale);
+            smallest_unit = Some(match smallest_unit {
+                Some(smallest) if smallest < unit => smallest,
+                _ => unit
+            });
+        }
+
+        let mut unit = smallest_unit.ok_or(ParseEffortError::Empty)?;
+        loop {
+            let amount = total_minutes / unit.minutes(scale);
+            if unit == Unit::Minutes || (amount - amount.round()).abs() < 1e-9 {
+                // a cast would clamp amounts beyond u64::MAX instead of failing
+                if !amount.is_finite() || amount.round() >= u64::MAX as f64 {
+                    return Err(ParseEffortError::TooLarge(s.trim().to_string()));
+                }
+                return Ok(unit.effort(amount.round() as u64));
+            }
+            unit = unit.smaller();
+        }
+    }
+}
+
+#[derive(Deserialize)]
+enum TaggedEffort {
+    Minutes(u64),
+    Hours(u64),
+    Days(u64),
+    Weeks(u64),
+    Months(u64),
+    Years(u64),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum EffortRepresentation {
+    Text(String),
+    Tagged(TaggedEffort),
+}
+
+impl<'de> Deserialize<'de> for Effort {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Effort, D::Error> {
+        Ok(match EffortRepresentation::deserialize(deserializer)? {
+            EffortRepresentation::Text(text) => text.parse().map_err(de::Error::custom)?,
+            EffortRepresentation::Tagged(tagged) => match tagged {
+                TaggedEffort::Minutes(m) => Effort::Minutes(m),
+                TaggedEffort::Hours(h) => Effort::Hours(h),
+                TaggedEffort::Days(d) => Effort::Days(d),
+                TaggedEffort::Weeks(w) => Effort::Weeks(w),
+                TaggedEffort::Months(m) => Effort::Months(m),
+                TaggedEffort::Years(y) => Effort::Years(y),
+            }
+        })
+    }
+}
@@ -0,0 +1,64 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use crate::traversal::Edge;
+
+pub const DEFAULT_DAMPING: f64 = 0.85;
+pub const CONVERGENCE_EPSILON: f64 = 1e-9;
+pub const MAX_ITERATIONS: u32 = 100;
+
+/// Distributes importance from the root aims along the contribution edges.
+///
+/// Every root aim is an entrance of importance: it receives an equal share of
+/// `(1 - damping)`. Each aim passes `damping` of its importance on to its
+/// contributing aims, proportionally to the connection weights. Cycles are handled
+/// by iterating until the scores change less than `epsilon` or `max_iterations`
+/// is reached.
+pub fn compute_importance<Id: Clone + Eq + Hash>(
+    roots: &[Id],
+    edges: &[Edge<Id>],
+    damping: f64,
+    epsilon: f64,
+    max_iterations: u32,
+) -> HashMap<Id, f64> {
+    let mut nodes: HashSet<Id> = roots.iter().cloned().collect();
+    let mut inbound_weight: HashMap<Id, u64> = HashMap::new();
+    for edge in edges {
+        nodes.insert(edge.contributing.clone());
+        nodes.insert(edge.receiving.clone());
+        *inbound_weight.entry(edge.receiving.clone()).or_insert(0) += edge.contribution as u64;
+    }
+
+    let base = if roots.is_empty() { 0.0 } else { (1.0 - damping) / roots.len() as f64 };
+    let root_set: HashSet<&Id> = roots.iter().collect();
+    let base_of = |id: &Id| if root_set.contains(id) { base } else { 0.0 };
+
+    let mut importance: HashMap<Id, f64> = nodes.iter()
+        .map(|id| (id.clone(), base_of(id)))
+        .collect();
+
+    for _ in 0..max_iterations {
+        let mut next: HashMap<Id, f64> = nodes.iter()
+            .map(|id| (id.clone(), base_of(id)))
+            .collect();
+        for edge in edges {
+            let total = inbound_weight.get(&edge.receiving).cloned().unwrap_or(0);
+            if total == 0 {
+                continue;
+            }
+            let share = edge.contribution as f64 / total as f64;
+            let flow = damping * importance[&edge.receiving] * share;
+            if let Some(contributing) = next.get_mut(&edge.contributing) {
+                *contributing += flow;
+            }
+        }
+        let delta = nodes.iter()
+            .map(|id| (next[id] - importance[id]).abs())
+            .fold(0.0, f64::max);
+        importance = next;
+        if delta < epsilon {
+            break;
+        }
+    }
+    importance
+}
@@ -0,0 +1,36 @@
+//! The contribution graph of aims, independent of holochain. The aims zome reads
+//! the graph from the DHT through an `AimStore`; tests use a `MemoryStore`.
+
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+
+pub mod comments;
+pub mod effort;
+pub mod importance;
+pub mod model;
+pub mod outline;
+pub mod paths;
+pub mod rollup;
+pub mod rules;
+pub mod store;
+pub mod traversal;
+
+pub use effort::{
+    Effort,
+    EffortScale,
+    ParseEffortError,
+};
+pub use model::{
+    Aim,
+    AimStatus,
+    Connection,
+    Direction,
+};
+pub use store::{
+    AimStore,
+    Connected,
+    MemoryStore,
+    NotFound,
+};
+pub use traversal::Edge;
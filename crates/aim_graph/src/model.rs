@@ -0,0 +1,77 @@
+use crate::effort::Effort;
+
+/// Where an aim stands in its lifecycle. Aims committed before the status was
+/// introduced deserialize as `Open`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AimStatus {
+    Open,
+    InProgress,
+    Achieved,
+    Abandoned,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for AimStatus {
+    fn default() -> AimStatus {
+        AimStatus::Open
+    }
+}
+
+impl AimStatus {
+    /// Achieved and abandoned aims can be reopened, everything else may move freely.
+    pub fn can_transition_to(&self, next: &AimStatus) -> bool {
+        match (self, next) {
+            (current, next) if current == next => true,
+            (AimStatus::Open, _) | (AimStatus::InProgress, _) => true,
+            (AimStatus::Achieved, AimStatus::InProgress) => true,
+            (AimStatus::Achieved, AimStatus::Open) => true,
+            (AimStatus::Abandoned, AimStatus::Open) => true,
+            _ => false,
+        }
+    }
+}
+
+/// The part of an aim the graph algorithms and content rules look at.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Aim {
+    pub title: String,
+    pub description: String,
+    pub effort: Effort,
+    pub color: [char; 6],
+    pub tags: Vec<String>,
+    pub status: AimStatus,
+    pub progress: u8,
+}
+
+impl Aim {
+    /// An open aim without description, tags or progress.
+    pub fn new(title: &str, effort: Effort) -> Aim {
+        Aim {
+            title: title.into(),
+            description: String::new(),
+            effort,
+            color: ['5'; 6],
+            tags: Vec::new(),
+            status: AimStatus::Open,
+            progress: 0,
+        }
+    }
+}
+
+/// `contributing` contributes to `receiving` with the given weight.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Connection<Id> {
+    pub contributing: Id,
+    pub receiving: Id,
+    pub contribution: u32,
+}
+
+/// Which way to walk from an aim: to the aims contributing to it, or to the aims
+/// it contributes to.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    Contributing,
+    Receiving,
+}
@@ -0,0 +1,219 @@
+use crate::effort::{
+    Effort,
+    EffortScale,
+};
+use crate::model::Aim;
+use crate::rules::validate_aim;
+
+/// An item of an imported outline, with the items nested in it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutlineNode {
+    pub title: String,
+    pub effort: Option<Effort>,
+    pub tags: Vec<String>,
+    pub children: Vec<OutlineNode>,
+}
+
+/// Takes an inline effort like "(3d)" and "#tags" out of an outline item.
+pub fn parse_item(text: &str, scale: &EffortScale) -> OutlineNode {
+    let mut effort = None;
+    let mut rest = text.to_string();
+    if let (Some(open), Some(close)) = (rest.rfind('('), rest.rfind(')')) {
+        if open < close {
+            if let Ok(parsed) = Effort::parse_with(&rest[open + 1..close], scale) {
+                effort = Some(parsed);
+                rest = format!("{}{}", &rest[..open], &rest[close + 1..]);
+            }
+        }
+    }
+    let mut tags = Vec::new();
+    let mut words = Vec::new();
+    for word in rest.split_whitespace() {
+        if word.len() > 1 && word.starts_with('#') {
+            tags.push(word[1..].to_string());
+        } else {
+            words.push(word);
+        }
+    }
+    OutlineNode {
+        title: words.join(" "),
+        effort,
+        tags,
+        children: Vec::new(),
+    }
+}
+
+fn attach(roots: &mut Vec<OutlineNode>, depth: usize, node: OutlineNode) {
+    let mut siblings = roots;
+    for _ in 0..depth {
+        if siblings.is_empty() {
+            break;
+        }
+        siblings = &mut siblings.last_mut().unwrap().children;
+    }
+    siblings.push(node);
+}
+
+/// Parses nested bullet lists ("-", "*", "+" or "1."). Nesting follows the
+/// indentation, other lines are ignored. Items of an empty item move up to its
+/// level.
+pub fn parse_markdown(content: &str, scale: &EffortScale) -> Vec<OutlineNode> {
+    let mut roots = Vec::new();
+    let mut indents: Vec<usize> = Vec::new();
+    for line in content.lines() {
+        let indent: usize = line.chars()
+            .take_while(|c| c.is_whitespace())
+            .map(|c| if c == '\t' { 4 } else { 1 })
+            .sum();
+        let trimmed = line.trim();
+        let item = if trimmed.starts_with("- ") || trimmed.starts_with("* ") || trimmed.starts_with("+ ") {
+            &trimmed[2..]
+        } else {
+            match trimmed.find(". ") {
+                Some(dot) if dot > 0 && trimmed[..dot].chars().all(|c| c.is_ascii_digit()) => &trimmed[dot + 2..],
+                _ => continue,
+            }
+        };
+        while let Some(&last) = indents.last() {
+            if last < indent {
+                break;
+            }
+            indents.pop();
+        }
+        let node = parse_item(item, scale);
+        if node.title.is_empty() {
+            continue;
+        }
+        attach(&mut roots, indents.len(), node);
+        indents.push(indent);
+    }
+    roots
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    match entity {
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "amp" => Some('&'),
+        _ if entity.starts_with("#x") || entity.starts_with("#X") => {
+            u32::from_str_radix(&entity[2..], 16).ok().and_then(std::char::from_u32)
+        },
+        _ if entity.starts_with('#') => entity[1..].parse().ok().and_then(std::char::from_u32),
+        _ => None,
+    }
+}
+
+/// Decodes the predefined XML entities and character references in one pass, so
+/// "&amp;lt;" stays "&lt;". Unknown entities are kept as they are.
+pub fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find(';').and_then(|end| decode_entity(&after[..end]).map(|c| (c, end))) {
+            Some((c, end)) => {
+                decoded.push(c);
+                rest = &after[end + 1..];
+            },
+            None => {
+                decoded.push('&');
+                rest = after;
+            },
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let pattern = format!("{}=", name);
+    let mut search_from = 0;
+    while let Some(found) = tag[search_from..].find(&pattern) {
+        let start = search_from + found;
+        let preceded_by_space = tag[..start].ends_with(char::is_whitespace);
+        let value_start = start + pattern.len();
+        let quote = tag[value_start..].chars().next()?;
+        if preceded_by_space && (quote == '"' || quote == '\'') {
+            let value_end = value_start + 1 + tag[value_start + 1..].find(quote)?;
+            return Some(decode_entities(&tag[value_start + 1..value_end]));
+        }
+        search_from = value_start;
+    }
+    None
+}
+
+/// The position of the '>' that closes the tag starting at the beginning of
+/// `content`. A '>' inside a quoted attribute value does not close the tag.
+fn tag_end(content: &str) -> Option<usize> {
+    if content.starts_with("<!--") {
+        return content.find("-->").map(|end| end + 2);
+    }
+    let mut quote = None;
+    for (index, c) in content.char_indices() {
+        match (quote, c) {
+            (Some(open), _) if c == open => quote = None,
+            (Some(_), _) => {},
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (None, '>') => return Some(index),
+            _ => {},
+        }
+    }
+    None
+}
+
+/// Parses the `outline` elements of an OPML document, using their `text` attribute.
+/// Outlines of an empty outline move up to its level.
+pub fn parse_opml(content: &str, scale: &EffortScale) -> Result<Vec<OutlineNode>, String> {
+    let mut roots = Vec::new();
+    // for every open outline, whether it was attached and so counts for the depth
+    let mut open_outlines: Vec<bool> = Vec::new();
+    let mut depth = 0;
+    let mut rest = content;
+    while let Some(open) = rest.find('<') {
+        let close = tag_end(&rest[open..])
+            .ok_or_else(|| String::from("unterminated tag in OPML"))? + open;
+        let tag = &rest[open + 1..close];
+        if tag.starts_with("/outline") {
+            match open_outlines.pop() {
+                Some(true) => depth -= 1,
+                Some(false) => {},
+                None => return Err("unbalanced </outline> in OPML".into()),
+            }
+        } else if tag.starts_with("outline") {
+            let text = attribute(tag, "text").unwrap_or_default();
+            let node = parse_item(&text, scale);
+            let attached = !node.title.is_empty();
+            if attached {
+                attach(&mut roots, depth, node);
+            }
+            if !tag.ends_with('/') {
+                open_outlines.push(attached);
+                if attached {
+                    depth += 1;
+                }
+            }
+        }
+        rest = &rest[close + 1..];
+    }
+    if !open_outlines.is_empty() {
+        return Err("unclosed <outline> in OPML".into());
+    }
+    Ok(roots)
+}
+
+/// Checks every item against the rules its aim will have to follow, so an import
+/// fails before it commits anything, and a dry run fails the same way.
+pub fn validate_outline(nodes: &[OutlineNode], default_effort: &Effort, scale: &EffortScale) -> Result<(), String> {
+    for node in nodes {
+        let aim = Aim {
+            tags: node.tags.clone(),
+            ..Aim::new(&node.title, node.effort.clone().unwrap_or_else(|| default_effort.clone()))
+        };
+        validate_aim(&aim, scale).map_err(|error| format!("\"{}\": {}", node.title, error))?;
+        validate_outline(&node.children, default_effort, scale)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use crate::model::Direction;
+use crate::store::AimStore;
+
+/// Upper bound of connections followed while searching paths, so aims with very
+/// many ancestors cannot stall the search.
+pub const MAX_PATH_SEARCH_STEPS: usize = 10_000;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PathStep<Id> {
+    pub aim_id: Id,
+    pub title: String,
+}
+
+/// A chain of aims from an upper aim down to the requested aim. The weight is the
+/// product of the contributions of the connections along the way.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Path<Id> {
+    pub steps: Vec<PathStep<Id>>,
+    pub weight: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Paths<Id> {
+    pub paths: Vec<Path<Id>>,
+    /// The search stopped after `MAX_PATH_SEARCH_STEPS` connections. Paths may be
+    /// missing, so the heaviest paths found need not be the heaviest ones there are.
+    pub incomplete: bool,
+}
+
+/// Finds the ways from `top` down to `aim_id` by following the connections of
+/// `aim_id` upwards, heaviest paths first and shorter ones first among equally
+/// heavy paths. With `max_paths` only the heaviest paths are returned.
+pub fn paths_to<S: AimStore>(
+    store: &S,
+    aim_id: &S::Id,
+    top: &S::Id,
+    max_paths: Option<usize>,
+) -> Result<Paths<S::Id>, S::Error> {
+    let start = PathStep {
+        aim_id: aim_id.clone(),
+        title: store.aim(aim_id)?.title,
+    };
+
+    let mut paths = Vec::new();
+    let mut followed = 0;
+    let mut incomplete = false;
+    // every stack item is a path from the requested aim upwards, with its weight
+    let mut stack: Vec<(Vec<PathStep<S::Id>>, f64)> = vec![(vec![start], 1.0)];
+    'search: while let Some((path, weight)) = stack.pop() {
+        let current = match path.last() {
+            Some(step) => step.aim_id.clone(),
+            None => continue,
+        };
+        if current == *top {
+            let mut steps = path;
+            steps.reverse();
+            paths.push(Path { steps, weight });
+            continue;
+        }
+        for receiving in store.connected(&current, Direction::Receiving)? {
+            followed += 1;
+            if followed > MAX_PATH_SEARCH_STEPS {
+                incomplete = true;
+                break 'search;
+            }
+            if path.iter().any(|step| step.aim_id == receiving.aim_id) {
+                continue;
+            }
+            let mut next = path.clone();
+            next.push(PathStep {
+                aim_id: receiving.aim_id,
+                title: receiving.aim.title,
+            });
+            stack.push((next, weight * receiving.contribution as f64));
+        }
+    }
+
+    paths.sort_by(|a, b| b.weight.partial_cmp(&a.weight).unwrap_or(std::cmp::Ordering::Equal)
+        .then_with(|| a.steps.len().cmp(&b.steps.len())));
+    if let Some(max_paths) = max_paths {
+        paths.truncate(max_paths);
+    }
+    Ok(Paths {
+        paths,
+        incomplete,
+    })
+}
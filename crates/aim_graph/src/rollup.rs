@@ -0,0 +1,170 @@
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+
+use crate::effort::{
+    Effort,
+    EffortScale,
+};
+use crate::model::{
+    Aim,
+    AimStatus,
+    Direction,
+};
+use crate::store::{
+    AimStore,
+    Connected,
+};
+
+/// Which share of each transitively contributing aim counts towards one aim.
+pub type Contributions<Id> = HashMap<Id, (f64, Aim)>;
+
+/// An aim on the current path of the search in `contributions`, with the
+/// contributors still to visit and what the contributions to it add up to.
+type Expanded<Id> = (Id, std::vec::IntoIter<Connected<Id>>, u64);
+
+fn expand<S: AimStore>(store: &S, aim_id: &S::Id) -> Result<Expanded<S::Id>, S::Error> {
+    let contributors = store.connected(aim_id, Direction::Contributing)?;
+    let total_contribution = contributors.iter()
+        .map(|connected| connected.contribution as u64)
+        .sum();
+    Ok((aim_id.clone(), contributors.into_iter(), total_contribution))
+}
+
+/// Walks all transitively contributing aims and records for each of them which
+/// share of its effort counts towards the starting aim. Unweighted, every aim
+/// counts fully; weighted, each connection passes on its share of the receiving
+/// aim's inbound contribution. Connections leading back onto the current path
+/// are skipped, so cycles do not loop.
+///
+/// Every aim is expanded once. The shares are then passed down in topological
+/// order, so aims reached over many paths cost no more than aims reached once.
+pub fn contributions<S: AimStore>(store: &S, aim_id: &S::Id, weighted: bool) -> Result<Contributions<S::Id>, S::Error> {
+    // for every expanded aim, its contributors with their share
+    let mut shares: HashMap<S::Id, Vec<(S::Id, f64)>> = HashMap::new();
+    let mut aims: HashMap<S::Id, Aim> = HashMap::new();
+    let mut on_path: HashSet<S::Id> = HashSet::new();
+    // aims in the order their search finished, contributors before the aims they
+    // contribute to
+    let mut finished: Vec<S::Id> = Vec::new();
+
+    shares.insert(aim_id.clone(), Vec::new());
+    on_path.insert(aim_id.clone());
+    let mut path = vec![expand(store, aim_id)?];
+    while let Some((current, contributors, total_contribution)) = path.last_mut() {
+        let connected = match contributors.next() {
+            Some(connected) => connected,
+            None => {
+                on_path.remove(current);
+                finished.push(current.clone());
+                path.pop();
+                continue;
+            },
+        };
+        if on_path.contains(&connected.aim_id) {
+            continue;
+        }
+        let share = if !weighted {
+            1.0
+        } else if *total_contribution == 0 {
+            0.0
+        } else {
+            connected.contribution as f64 / *total_contribution as f64
+        };
+        let current = current.clone();
+        shares.get_mut(&current).unwrap().push((connected.aim_id.clone(), share));
+        if !shares.contains_key(&connected.aim_id) {
+            shares.insert(connected.aim_id.clone(), Vec::new());
+            on_path.insert(connected.aim_id.clone());
+            path.push(expand(store, &connected.aim_id)?);
+            aims.insert(connected.aim_id, connected.aim);
+        }
+    }
+
+    let mut factors: HashMap<S::Id, f64> = HashMap::new();
+    factors.insert(aim_id.clone(), 1.0);
+    for receiving in finished.iter().rev() {
+        let factor = factors.get(receiving).cloned().unwrap_or(0.0);
+        for (contributing, share) in shares[receiving].iter() {
+            *factors.entry(contributing.clone()).or_insert(0.0) += factor * share;
+        }
+    }
+    Ok(aims.into_iter()
+        .map(|(id, aim)| {
+            let factor = factors.get(&id).cloned().unwrap_or(0.0).min(1.0);
+            (id, (factor, aim))
+        })
+        .collect())
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SubtreeTotals {
+    pub own_effort: Effort,
+    pub contributing_effort: Effort,
+    pub total_effort: Effort,
+    pub contributing_aim_count: u32,
+}
+
+/// Sums up the effort of all aims that transitively contribute to the given aim,
+/// optionally weighted by the contribution of the connections on the way.
+pub fn subtree_effort<S: AimStore>(
+    store: &S,
+    aim_id: &S::Id,
+    weighted: bool,
+    scale: &EffortScale,
+) -> Result<SubtreeTotals, S::Error> {
+    let own_effort = store.aim(aim_id)?.effort;
+    let contributions = contributions(store, aim_id, weighted)?;
+
+    let contributing_minutes: f64 = contributions.values()
+        .map(|(factor, aim)| factor * aim.effort.to_minutes(scale) as f64)
+        .sum();
+    let contributing_effort = Effort::from_minutes(contributing_minutes.round() as u64, scale);
+
+    Ok(SubtreeTotals {
+        total_effort: own_effort.add_with(&contributing_effort, scale),
+        own_effort: own_effort.normalized(scale),
+        contributing_effort,
+        contributing_aim_count: contributions.len() as u32,
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Completion {
+    pub contributing_effort: Effort,
+    pub done_effort: Effort,
+    /// Share of the contributing effort that is done, in percent. Abandoned aims do
+    /// not count as contributing effort.
+    pub completion: f64,
+}
+
+/// Reports how much of the effort of all transitively contributing aims is done,
+/// counting each aim with its progress. Without contributing effort, the aim's own
+/// progress is its completion.
+pub fn completion<S: AimStore>(store: &S, aim_id: &S::Id, scale: &EffortScale) -> Result<Completion, S::Error> {
+    let aim = store.aim(aim_id)?;
+    let contributions = contributions(store, aim_id, false)?;
+
+    let (total_minutes, done_minutes) = contributions.values()
+        .filter(|(_, contributing)| contributing.status != AimStatus::Abandoned)
+        .fold((0u64, 0u64), |(total, done), (_, contributing)| {
+            let minutes = contributing.effort.to_minutes(scale);
+            let progress = match contributing.status {
+                AimStatus::Achieved => 100,
+                _ => contributing.progress.min(100) as u64,
+            };
+            (total.saturating_add(minutes), done.saturating_add(minutes / 100 * progress + minutes % 100 * progress / 100))
+        });
+    let completion = if total_minutes == 0 {
+        aim.progress as f64
+    } else {
+        done_minutes as f64 * 100.0 / total_minutes as f64
+    };
+
+    Ok(Completion {
+        contributing_effort: Effort::from_minutes(total_minutes, scale),
+        done_effort: Effort::from_minutes(done_minutes, scale),
+        completion,
+    })
+}
@@ -0,0 +1,80 @@
+use crate::effort::EffortScale;
+use crate::model::{
+    Aim,
+    Connection,
+};
+
+pub const MAX_TITLE_LENGTH: usize = 200;
+pub const MAX_DESCRIPTION_LENGTH: usize = 10_000;
+pub const MAX_TAG_COUNT: usize = 20;
+pub const MAX_TAG_LENGTH: usize = 50;
+/// The largest effort of a single aim, in working minutes. It leaves room to sum
+/// up the efforts of whole graphs without coming near `u64::MAX`.
+pub const MAX_EFFORT_MINUTES: u64 = 1_000_000_000;
+/// How far an aim's `timestamp_ms` may lie ahead of the header time, to allow for
+/// clocks that are slightly off.
+pub const MAX_CLOCK_SKEW_MS: i64 = 5 * 60 * 1000;
+
+pub fn validate_aim(aim: &Aim, scale: &EffortScale) -> Result<(), String> {
+    let title_length = aim.title.chars().count();
+    if aim.title.trim().is_empty() {
+        return Err("the title of an aim must not be empty".into());
+    }
+    if title_length > MAX_TITLE_LENGTH {
+        return Err(format!("the title of an aim must not be longer than {} characters", MAX_TITLE_LENGTH));
+    }
+    if aim.description.chars().count() > MAX_DESCRIPTION_LENGTH {
+        return Err(format!("the description of an aim must not be longer than {} characters", MAX_DESCRIPTION_LENGTH));
+    }
+    if !aim.color.iter().all(|c| c.is_ascii_hexdigit()) {
+        return Err("the color of an aim must consist of hex digits".into());
+    }
+    if aim.tags.len() > MAX_TAG_COUNT {
+        return Err(format!("an aim must not have more than {} tags", MAX_TAG_COUNT));
+    }
+    for tag in aim.tags.iter() {
+        let tag_length = tag.chars().count();
+        if tag.trim().is_empty() || tag_length > MAX_TAG_LENGTH {
+            return Err(format!("tags must have between 1 and {} characters", MAX_TAG_LENGTH));
+        }
+    }
+    match aim.effort.checked_minutes(scale) {
+        Some(minutes) if minutes <= MAX_EFFORT_MINUTES => {},
+        _ => return Err(format!("the effort of an aim must not exceed {} working minutes", MAX_EFFORT_MINUTES)),
+    }
+    if aim.progress > 100 {
+        return Err("the progress of an aim must not exceed 100%".into());
+    }
+    Ok(())
+}
+
+/// Checks the `timestamp_ms` of an aim against the time of the header committing it.
+pub fn validate_timestamp(timestamp_ms: i64, header_ms: i64) -> Result<(), String> {
+    if timestamp_ms < 0 {
+        return Err("timestamp_ms must not be negative".into());
+    }
+    if timestamp_ms > header_ms + MAX_CLOCK_SKEW_MS {
+        return Err("timestamp_ms must not lie in the future".into());
+    }
+    Ok(())
+}
+
+/// The rules a connection has to follow on its own. Whether both ends are aims
+/// depends on the storage and is checked there.
+pub fn validate_connection<Id: PartialEq>(connection: &Connection<Id>) -> Result<(), String> {
+    if connection.contribution == 0 {
+        return Err("the contribution of a connection must be greater than zero".into());
+    }
+    if connection.contributing == connection.receiving {
+        return Err("an aim cannot contribute to itself".into());
+    }
+    Ok(())
+}
+
+/// Status and progress may only change along `AimStatus::can_transition_to`.
+pub fn validate_status_transition(old_aim: &Aim, new_aim: &Aim) -> Result<(), String> {
+    if !old_aim.status.can_transition_to(&new_aim.status) {
+        return Err(format!("an aim cannot go from {:?} to {:?}", old_aim.status, new_aim.status));
+    }
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+
+use crate::model::{
+    Aim,
+    Connection,
+    Direction,
+};
+
+/// An aim reached over one connection, together with that connection.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Connected<Id> {
+    pub aim_id: Id,
+    pub aim: Aim,
+    pub connection_id: Id,
+    pub contribution: u32,
+}
+
+/// Where the graph algorithms read aims and connections from. The zome implements
+/// it on top of the DHT; `MemoryStore` keeps everything in memory.
+pub trait AimStore {
+    type Id: Clone + Eq + Hash;
+    type Error;
+
+    fn aim(&self, id: &Self::Id) -> Result<Aim, Self::Error>;
+
+    /// The aims connected to `id` in the given direction, in a stable order.
+    fn connected(&self, id: &Self::Id, direction: Direction) -> Result<Vec<Connected<Self::Id>>, Self::Error>;
+}
+
+/// `MemoryStore` has no aim with this id.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotFound<Id>(pub Id);
+
+impl<Id: fmt::Debug> fmt::Display for NotFound<Id> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "no aim with id {:?}", self.0)
+    }
+}
+
+/// Aims and connections kept in memory, listed in the order they were added.
+#[derive(Debug, Clone)]
+pub struct MemoryStore<Id> {
+    aims: HashMap<Id, Aim>,
+    connections: Vec<(Id, Connection<Id>)>,
+}
+
+impl<Id: Clone + Eq + Hash> Default for MemoryStore<Id> {
+    fn default() -> MemoryStore<Id> {
+        MemoryStore {
+            aims: HashMap::new(),
+            connections: Vec::new(),
+        }
+    }
+}
+
+impl<Id: Clone + Eq + Hash> MemoryStore<Id> {
+    pub fn new() -> MemoryStore<Id> {
+        MemoryStore::default()
+    }
+
+    /// Adds the aim, or replaces the one with the same id.
+    pub fn insert_aim(&mut self, id: Id, aim: Aim) {
+        self.aims.insert(id, aim);
+    }
+
+    /// Adds a connection, or replaces the one with the same id.
+    pub fn connect(&mut self, id: Id, connection: Connection<Id>) {
+        match self.connections.iter_mut().find(|(existing, _)| *existing == id) {
+            Some(existing) => existing.1 = connection,
+            None => self.connections.push((id, connection)),
+        }
+    }
+
+    pub fn disconnect(&mut self, id: &Id) {
+        self.connections.retain(|(existing, _)| existing != id);
+    }
+
+    pub fn connections(&self) -> impl Iterator<Item = &(Id, Connection<Id>)> {
+        self.connections.iter()
+    }
+}
+
+impl<Id: Clone + Eq + Hash> AimStore for MemoryStore<Id> {
+    type Id = Id;
+    type Error = NotFound<Id>;
+
+    fn aim(&self, id: &Id) -> Result<Aim, NotFound<Id>> {
+        self.aims.get(id).cloned().ok_or_else(|| NotFound(id.clone()))
+    }
+
+    fn connected(&self, id: &Id, direction: Direction) -> Result<Vec<Connected<Id>>, NotFound<Id>> {
+        if !self.aims.contains_key(id) {
+            return Err(NotFound(id.clone()));
+        }
+        let mut connected = Vec::new();
+        for (connection_id, connection) in self.connections.iter() {
+            let other = match direction {
+                Direction::Contributing if connection.receiving == *id => &connection.contributing,
+                Direction::Receiving if connection.contributing == *id => &connection.receiving,
+                _ => continue,
+            };
+            connected.push(Connected {
+                aim_id: other.clone(),
+                aim: self.aim(other)?,
+                connection_id: connection_id.clone(),
+                contribution: connection.contribution,
+            });
+        }
+        Ok(connected)
+    }
+}
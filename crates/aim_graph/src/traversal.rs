@@ -0,0 +1,37 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::model::Direction;
+use crate::store::AimStore;
+
+/// An edge of the contribution graph: `contributing` contributes to `receiving`
+/// with the weight stored in the connection.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Edge<Id> {
+    pub contributing: Id,
+    pub receiving: Id,
+    pub contribution: u32,
+}
+
+/// Walks the contribution graph breadth first from the given roots towards the
+/// contributing aims and returns every edge that was reached, once.
+pub fn collect_edges<S: AimStore>(store: &S, roots: &[S::Id]) -> Result<Vec<Edge<S::Id>>, S::Error> {
+    let mut visited: HashSet<S::Id> = HashSet::new();
+    let mut queue: VecDeque<S::Id> = roots.iter().cloned().collect();
+    let mut edges = Vec::new();
+    while let Some(receiving) = queue.pop_front() {
+        if !visited.insert(receiving.clone()) {
+            continue;
+        }
+        for connected in store.connected(&receiving, Direction::Contributing)? {
+            edges.push(Edge {
+                contributing: connected.aim_id.clone(),
+                receiving: receiving.clone(),
+                contribution: connected.contribution,
+            });
+            if !visited.contains(&connected.aim_id) {
+                queue.push_back(connected.aim_id);
+            }
+        }
+    }
+    Ok(edges)
+}
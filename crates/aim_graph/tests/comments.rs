@@ -0,0 +1,90 @@
+extern crate aim_graph;
+
+use aim_graph::comments::{
+    comment_deletion,
+    prune_deleted,
+    validate_comment_edit,
+    validate_comment_text,
+    CommentDeletion,
+    Thread,
+    MAX_COMMENT_LENGTH,
+};
+
+#[derive(Debug, PartialEq)]
+struct TestThread {
+    id: u32,
+    deleted: bool,
+    replies: Vec<TestThread>,
+}
+
+impl Thread for TestThread {
+    fn is_deleted(&self) -> bool {
+        self.deleted
+    }
+
+    fn replies(&self) -> &[TestThread] {
+        &self.replies
+    }
+
+    fn replies_mut(&mut self) -> &mut Vec<TestThread> {
+        &mut self.replies
+    }
+}
+
+fn comment(id: u32, replies: Vec<TestThread>) -> TestThread {
+    TestThread { id, deleted: false, replies }
+}
+
+fn tombstone(id: u32, replies: Vec<TestThread>) -> TestThread {
+    TestThread { id, deleted: true, replies }
+}
+
+fn ids(threads: &[TestThread]) -> Vec<u32> {
+    threads.iter().map(|thread| thread.id).collect()
+}
+
+#[test]
+fn comments_with_replies_become_tombstones() {
+    assert_eq!(comment_deletion(0), CommentDeletion::Remove);
+    assert_eq!(comment_deletion(1), CommentDeletion::Tombstone);
+    assert_eq!(comment_deletion(5), CommentDeletion::Tombstone);
+}
+
+#[test]
+fn checks_the_text_of_comments_and_tombstones() {
+    assert_eq!(validate_comment_text("nice aim", false), Ok(()));
+    assert!(validate_comment_text(" \n", false).is_err());
+    assert!(validate_comment_text(&"a".repeat(MAX_COMMENT_LENGTH + 1), false).is_err());
+    assert_eq!(validate_comment_text("", true), Ok(()));
+    assert!(validate_comment_text("still here", true).is_err());
+}
+
+#[test]
+fn tombstones_cannot_be_edited_or_restored() {
+    assert_eq!(validate_comment_edit(false, "edited", false), Ok(()));
+    assert_eq!(validate_comment_edit(false, "", true), Ok(()));
+    assert!(validate_comment_edit(true, "back again", false).is_err());
+    assert!(validate_comment_edit(true, "", true).is_err());
+}
+
+#[test]
+fn keeps_tombstones_that_still_have_replies() {
+    let mut threads = vec![
+        tombstone(1, vec![comment(2, vec![])]),
+        comment(3, vec![]),
+    ];
+    prune_deleted(&mut threads);
+    assert_eq!(ids(&threads), vec![1, 3]);
+    assert_eq!(ids(&threads[0].replies), vec![2]);
+}
+
+#[test]
+fn prunes_tombstones_whose_replies_are_all_gone() {
+    let mut threads = vec![
+        tombstone(1, vec![]),
+        tombstone(2, vec![tombstone(3, vec![tombstone(4, vec![])])]),
+        comment(5, vec![tombstone(6, vec![])]),
+    ];
+    prune_deleted(&mut threads);
+    assert_eq!(threads, vec![comment(5, vec![])]);
+}
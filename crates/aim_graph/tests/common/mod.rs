@@ -0,0 +1,27 @@
+use aim_graph::{
+    Aim,
+    Connection,
+    Effort,
+    MemoryStore,
+};
+
+/// Builds a store from `(id, effort)` aims and `(contributing, receiving,
+/// contribution)` connections. Connection ids are "contributing->receiving".
+pub fn store(aims: &[(&'static str, Effort)], connections: &[(&'static str, &'static str, u32)]) -> MemoryStore<String> {
+    let mut store = MemoryStore::new();
+    for (id, effort) in aims {
+        store.insert_aim(id.to_string(), Aim::new(id, effort.clone()));
+    }
+    for (contributing, receiving, contribution) in connections {
+        store.connect(format!("{}->{}", contributing, receiving), Connection {
+            contributing: contributing.to_string(),
+            receiving: receiving.to_string(),
+            contribution: *contribution,
+        });
+    }
+    store
+}
+
+pub fn id(id: &str) -> String {
+    id.to_string()
+}
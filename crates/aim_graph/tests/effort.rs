@@ -0,0 +1,116 @@
+extern crate aim_graph;
+extern crate serde_json;
+
+use aim_graph::{
+    Effort,
+    EffortScale,
+    ParseEffortError,
+};
+
+#[test]
+fn parses_single_units() {
+    assert_eq!("10min".parse::<Effort>(), Ok(Effort::Minutes(10)));
+    assert_eq!("3h".parse::<Effort>(), Ok(Effort::Hours(3)));
+    assert_eq!("2d".parse::<Effort>(), Ok(Effort::Days(2)));
+    assert_eq!("1w".parse::<Effort>(), Ok(Effort::Weeks(1)));
+    assert_eq!("6m".parse::<Effort>(), Ok(Effort::Months(6)));
+    assert_eq!("100y".parse::<Effort>(), Ok(Effort::Years(100)));
+}
+
+#[test]
+fn parses_compound_and_fractional_efforts_as_working_time() {
+    let week_and_days = "1w 2d".parse::<Effort>().unwrap();
+    assert!(matches!(week_and_days, Effort::Days(7)));
+    assert!(matches!(Effort::Weeks(1) + Effort::Days(2), Effort::Days(7)));
+    let day_and_a_half = "1.5d".parse::<Effort>().unwrap();
+    assert!(matches!(day_and_a_half, Effort::Hours(12)));
+    let hour_and_a_half = "1.5h".parse::<Effort>().unwrap();
+    assert!(matches!(hour_and_a_half, Effort::Minutes(90)));
+    let year_and_month = "1y 1m".parse::<Effort>().unwrap();
+    assert!(matches!(year_and_month, Effort::Months(13)));
+}
+
+#[test]
+fn parses_with_other_scales() {
+    let scale = EffortScale {
+        working_hours_per_day: 6,
+        working_days_per_week: 4,
+    };
+    assert!(matches!(Effort::parse_with("1w 2d", &scale), Ok(Effort::Days(6))));
+    assert!(matches!(Effort::parse_with("0.5d", &scale), Ok(Effort::Hours(3))));
+    let parsed = Effort::parse_with("2d 3h", &scale).unwrap();
+    assert_eq!(parsed.to_minutes(&scale), Effort::Days(2).add_with(&Effort::Hours(3), &scale).to_minutes(&scale));
+}
+
+#[test]
+fn rejects_malformed_efforts() {
+    assert_eq!("".parse::<Effort>(), Err(ParseEffortError::Empty));
+    assert_eq!("3".parse::<Effort>(), Err(ParseEffortError::MissingUnit("3".into())));
+    assert_eq!("3x".parse::<Effort>(), Err(ParseEffortError::UnknownUnit("x".into())));
+    assert_eq!("h".parse::<Effort>(), Err(ParseEffortError::InvalidNumber("h".into())));
+}
+
+#[test]
+fn displays_the_short_form() {
+    assert_eq!(Effort::Minutes(10).to_string(), "10min");
+    assert_eq!(Effort::Years(2).to_string(), "2y");
+    let effort = Effort::Days(9);
+    assert_eq!(effort.to_string().parse::<Effort>(), Ok(effort));
+}
+
+#[test]
+fn converts_with_the_scale() {
+    let scale = EffortScale::default();
+    assert_eq!(Effort::Days(1).to_minutes(&scale), 8 * 60);
+    assert_eq!(Effort::Weeks(1).to_minutes(&scale), 5 * 8 * 60);
+    assert!(matches!(Effort::from_minutes(16 * 60, &scale), Effort::Days(2)));
+    assert!(matches!(Effort::from_minutes(90, &scale), Effort::Minutes(90)));
+    assert!(matches!(Effort::Hours(40).normalized(&scale), Effort::Weeks(1)));
+
+    let long_days = EffortScale {
+        working_hours_per_day: 10,
+        working_days_per_week: 5,
+    };
+    assert_eq!(Effort::Days(1).to_minutes(&long_days), 10 * 60);
+}
+
+#[test]
+fn compares_and_adds_by_working_time() {
+    assert_eq!(Effort::Hours(8), Effort::Days(1));
+    assert!(Effort::Days(6) > Effort::Weeks(1));
+    assert!(matches!(Effort::Days(3) + Effort::Days(2), Effort::Weeks(1)));
+    let sum: Effort = vec![Effort::Hours(1), Effort::Minutes(30)].into_iter().sum();
+    assert!(matches!(sum, Effort::Minutes(90)));
+}
+
+#[test]
+fn deserializes_tagged_and_short_forms() {
+    let tagged: Effort = serde_json::from_str(r#"{"Days": 3}"#).unwrap();
+    assert!(matches!(tagged, Effort::Days(3)));
+    let short: Effort = serde_json::from_str(r#""1w 2d""#).unwrap();
+    assert!(matches!(short, Effort::Days(7)));
+    assert!(serde_json::from_str::<Effort>(r#""3x""#).is_err());
+    assert_eq!(serde_json::to_string(&Effort::Hours(2)).unwrap(), r#"{"Hours":2}"#);
+}
+
+#[test]
+fn rejects_efforts_beyond_u64() {
+    assert_eq!(
+        "100000000000000000000y".parse::<Effort>(),
+        Err(ParseEffortError::TooLarge("100000000000000000000y".into()))
+    );
+    assert_eq!(
+        "18446744073709551616min".parse::<Effort>(),
+        Err(ParseEffortError::TooLarge("18446744073709551616min".into()))
+    );
+}
+
+#[test]
+fn saturates_instead_of_overflowing() {
+    let scale = EffortScale::default();
+    let huge = Effort::Years(u64::MAX);
+    assert_eq!(huge.checked_minutes(&scale), None);
+    assert_eq!(huge.to_minutes(&scale), u64::MAX);
+    assert_eq!(huge.add_with(&Effort::Days(1), &scale).to_minutes(&scale), u64::MAX);
+    assert!(Effort::Days(1) < huge);
+}
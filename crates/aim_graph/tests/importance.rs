@@ -0,0 +1,65 @@
+extern crate aim_graph;
+
+mod common;
+
+use aim_graph::{
+    Edge,
+    Effort,
+};
+use aim_graph::importance::{
+    compute_importance,
+    CONVERGENCE_EPSILON,
+    DEFAULT_DAMPING,
+    MAX_ITERATIONS,
+};
+use aim_graph::traversal::collect_edges;
+
+use common::{id, store};
+
+fn edge(contributing: &str, receiving: &str, contribution: u32) -> Edge<String> {
+    Edge {
+        contributing: id(contributing),
+        receiving: id(receiving),
+        contribution,
+    }
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-6
+}
+
+#[test]
+fn roots_share_the_entrance_of_importance() {
+    let importance = compute_importance(&[id("x"), id("y")], &[], DEFAULT_DAMPING, CONVERGENCE_EPSILON, MAX_ITERATIONS);
+    assert!(close(importance[&id("x")], 0.075));
+    assert!(close(importance[&id("y")], 0.075));
+}
+
+#[test]
+fn importance_flows_by_contribution() {
+    let edges = vec![edge("a", "root", 3), edge("b", "root", 1), edge("c", "a", 1)];
+    let importance = compute_importance(&[id("root")], &edges, 0.5, CONVERGENCE_EPSILON, MAX_ITERATIONS);
+    assert!(close(importance[&id("root")], 0.5));
+    assert!(close(importance[&id("a")], 0.1875));
+    assert!(close(importance[&id("b")], 0.0625));
+    assert!(close(importance[&id("c")], 0.09375));
+}
+
+#[test]
+fn converges_on_cycles() {
+    let store = store(
+        &[("root", Effort::Years(1)), ("a", Effort::Days(1)), ("b", Effort::Days(1))],
+        &[("a", "root", 1), ("b", "a", 1), ("a", "b", 1)],
+    );
+    let edges = collect_edges(&store, &[id("root")]).unwrap();
+    let importance = compute_importance(&[id("root")], &edges, DEFAULT_DAMPING, CONVERGENCE_EPSILON, MAX_ITERATIONS);
+    assert_eq!(importance.len(), 3);
+    assert!(importance.values().all(|value| value.is_finite() && *value > 0.0));
+    assert!(importance[&id("a")] > importance[&id("b")]);
+}
+
+#[test]
+fn nothing_is_important_without_roots() {
+    let importance = compute_importance(&[], &[edge("a", "b", 1)], DEFAULT_DAMPING, CONVERGENCE_EPSILON, MAX_ITERATIONS);
+    assert!(importance.values().all(|value| *value == 0.0));
+}
@@ -0,0 +1,118 @@
+extern crate aim_graph;
+
+use aim_graph::{
+    Effort,
+    EffortScale,
+};
+use aim_graph::outline::{
+    decode_entities,
+    parse_item,
+    parse_markdown,
+    parse_opml,
+    validate_outline,
+    OutlineNode,
+};
+use aim_graph::rules::MAX_TITLE_LENGTH;
+
+fn titles(nodes: &[OutlineNode]) -> Vec<&str> {
+    nodes.iter().map(|node| node.title.as_str()).collect()
+}
+
+#[test]
+fn parses_effort_and_tags_of_an_item() {
+    let node = parse_item("write the book (2w) #writing #big", &EffortScale::default());
+    assert_eq!(node.title, "write the book");
+    assert_eq!(node.effort, Some(Effort::Weeks(2)));
+    assert_eq!(node.tags, vec!["writing", "big"]);
+}
+
+#[test]
+fn nests_markdown_by_indentation() {
+    let content = "# plan\n- house\n  - walls (3d)\n    1. bricks\n  - roof\n* garden\n";
+    let roots = parse_markdown(content, &EffortScale::default());
+    assert_eq!(titles(&roots), vec!["house", "garden"]);
+    assert_eq!(titles(&roots[0].children), vec!["walls", "roof"]);
+    assert_eq!(roots[0].children[0].effort, Some(Effort::Days(3)));
+    assert_eq!(titles(&roots[0].children[0].children), vec!["bricks"]);
+}
+
+#[test]
+fn lifts_the_items_of_empty_markdown_items() {
+    let roots = parse_markdown("- house\n  - #tag-only\n    - walls\n", &EffortScale::default());
+    assert_eq!(titles(&roots), vec!["house"]);
+    assert_eq!(titles(&roots[0].children), vec!["walls"]);
+}
+
+#[test]
+fn nests_opml_outlines() {
+    let content = r#"<?xml version="1.0"?>
+        <opml version="2.0"><body>
+            <outline text="house">
+                <outline text="walls (3d)"/>
+                <outline text="roof"><outline text="tiles"/></outline>
+            </outline>
+            <outline text="garden"/>
+        </body></opml>"#;
+    let roots = parse_opml(content, &EffortScale::default()).unwrap();
+    assert_eq!(titles(&roots), vec!["house", "garden"]);
+    assert_eq!(titles(&roots[0].children), vec!["walls", "roof"]);
+    assert_eq!(roots[0].children[0].effort, Some(Effort::Days(3)));
+    assert_eq!(titles(&roots[0].children[1].children), vec!["tiles"]);
+}
+
+#[test]
+fn reads_opml_attributes_containing_angle_brackets() {
+    let content = r#"<opml><body><outline text="a > b" note='x > y'><outline text="c"/></outline></body></opml>"#;
+    let roots = parse_opml(content, &EffortScale::default()).unwrap();
+    assert_eq!(titles(&roots), vec!["a > b"]);
+    assert_eq!(titles(&roots[0].children), vec!["c"]);
+}
+
+#[test]
+fn decodes_entities_once() {
+    assert_eq!(decode_entities("fish &amp; chips &lt;3"), "fish & chips <3");
+    assert_eq!(decode_entities("&amp;lt;"), "&lt;");
+    assert_eq!(decode_entities("&#233;t&#xE9;"), "été");
+    assert_eq!(decode_entities("&unknown; & more"), "&unknown; & more");
+
+    let content = r#"<opml><body><outline text="R&amp;D &quot;lab&quot;"/></body></opml>"#;
+    let roots = parse_opml(content, &EffortScale::default()).unwrap();
+    assert_eq!(titles(&roots), vec!["R&D \"lab\""]);
+}
+
+#[test]
+fn lifts_the_outlines_of_empty_opml_outlines() {
+    let content = r#"<opml><body>
+        <outline text="house">
+            <outline text="">
+                <outline text="walls"/>
+            </outline>
+            <outline text="roof"/>
+        </outline>
+        <outline text="garden"/>
+    </body></opml>"#;
+    let roots = parse_opml(content, &EffortScale::default()).unwrap();
+    assert_eq!(titles(&roots), vec!["house", "garden"]);
+    assert_eq!(titles(&roots[0].children), vec!["walls", "roof"]);
+    assert!(roots[0].children[0].children.is_empty());
+}
+
+#[test]
+fn rejects_unbalanced_opml() {
+    let scale = EffortScale::default();
+    assert!(parse_opml("<outline text=\"a\">", &scale).is_err());
+    assert!(parse_opml("</outline>", &scale).is_err());
+    assert!(parse_opml("<outline text=\"a", &scale).is_err());
+}
+
+#[test]
+fn validates_every_item_of_an_outline() {
+    let scale = EffortScale::default();
+    let default_effort = Effort::Days(1);
+    let fine = parse_markdown("- house\n  - walls #build\n", &scale);
+    assert_eq!(validate_outline(&fine, &default_effort, &scale), Ok(()));
+
+    let long_title = format!("- house\n  - {}\n", "a".repeat(MAX_TITLE_LENGTH + 1));
+    let too_long = parse_markdown(&long_title, &scale);
+    assert!(validate_outline(&too_long, &default_effort, &scale).is_err());
+}
@@ -0,0 +1,96 @@
+extern crate aim_graph;
+
+mod common;
+
+use aim_graph::{
+    Aim,
+    Connection,
+    Effort,
+    MemoryStore,
+    NotFound,
+};
+use aim_graph::paths::{
+    paths_to,
+    MAX_PATH_SEARCH_STEPS,
+};
+
+use common::{id, store};
+
+fn titles(steps: &[aim_graph::paths::PathStep<String>]) -> Vec<&str> {
+    steps.iter().map(|step| step.title.as_str()).collect()
+}
+
+#[test]
+fn finds_all_paths_heaviest_first() {
+    let store = store(
+        &[("root", Effort::Years(1)), ("a", Effort::Days(1)), ("b", Effort::Days(1)), ("leaf", Effort::Days(1))],
+        &[("a", "root", 1), ("b", "root", 5), ("leaf", "a", 2), ("leaf", "b", 1), ("leaf", "root", 4)],
+    );
+    let paths = paths_to(&store, &id("leaf"), &id("root"), None).unwrap().paths;
+    assert_eq!(paths.len(), 3);
+    assert_eq!(titles(&paths[0].steps), vec!["root", "b", "leaf"]);
+    assert_eq!(paths[0].weight, 5.0);
+    assert_eq!(titles(&paths[1].steps), vec!["root", "leaf"]);
+    assert_eq!(titles(&paths[2].steps), vec!["root", "a", "leaf"]);
+}
+
+#[test]
+fn limits_the_number_of_paths() {
+    let store = store(
+        &[("root", Effort::Years(1)), ("a", Effort::Days(1)), ("leaf", Effort::Days(1))],
+        &[("a", "root", 1), ("leaf", "a", 1), ("leaf", "root", 1)],
+    );
+    let paths = paths_to(&store, &id("leaf"), &id("root"), Some(1)).unwrap().paths;
+    assert_eq!(paths.len(), 1);
+    // equally heavy, the shorter path wins
+    assert_eq!(titles(&paths[0].steps), vec!["root", "leaf"]);
+}
+
+#[test]
+fn skips_cycles_and_unconnected_aims() {
+    let store = store(
+        &[("root", Effort::Years(1)), ("a", Effort::Days(1)), ("b", Effort::Days(1)), ("island", Effort::Days(1))],
+        &[("a", "b", 1), ("b", "a", 1), ("a", "root", 1)],
+    );
+    let paths = paths_to(&store, &id("b"), &id("root"), None).unwrap().paths;
+    assert_eq!(paths.len(), 1);
+    assert_eq!(titles(&paths[0].steps), vec!["root", "a", "b"]);
+    assert!(paths_to(&store, &id("island"), &id("root"), None).unwrap().paths.is_empty());
+}
+
+#[test]
+fn the_top_aim_is_a_path_of_its_own() {
+    let store = store(&[("root", Effort::Years(1))], &[]);
+    let paths = paths_to(&store, &id("root"), &id("root"), None).unwrap().paths;
+    assert_eq!(paths.len(), 1);
+    assert_eq!(paths[0].weight, 1.0);
+    assert_eq!(paths_to(&store, &id("missing"), &id("root"), None).map(|found| found.paths), Err(NotFound(id("missing"))));
+}
+
+#[test]
+fn reports_searches_that_hit_the_step_limit() {
+    // layers of two aims, each contributing to both aims of the layer above
+    let mut layered = MemoryStore::new();
+    layered.insert_aim(id("root"), Aim::new("root", Effort::Years(1)));
+    let mut upper = vec![id("root")];
+    for layer in 0..20 {
+        let lower: Vec<String> = (0..2).map(|index| format!("{}.{}", layer, index)).collect();
+        for contributing in lower.iter() {
+            layered.insert_aim(contributing.clone(), Aim::new(contributing, Effort::Days(1)));
+            for receiving in upper.iter() {
+                layered.connect(format!("{}->{}", contributing, receiving), Connection {
+                    contributing: contributing.clone(),
+                    receiving: receiving.clone(),
+                    contribution: 1,
+                });
+            }
+        }
+        upper = lower;
+    }
+    let found = paths_to(&layered, &upper[0], &id("root"), None).unwrap();
+    assert!(found.incomplete);
+    assert!(found.paths.len() < MAX_PATH_SEARCH_STEPS);
+
+    let small = store(&[("root", Effort::Years(1)), ("leaf", Effort::Days(1))], &[("leaf", "root", 1)]);
+    assert!(!paths_to(&small, &id("leaf"), &id("root"), None).unwrap().incomplete);
+}
@@ -0,0 +1,123 @@
+extern crate aim_graph;
+
+mod common;
+
+use aim_graph::{
+    Aim,
+    AimStatus,
+    AimStore,
+    Connection,
+    Effort,
+    EffortScale,
+    MemoryStore,
+};
+use aim_graph::rollup::{
+    completion,
+    contributions,
+    subtree_effort,
+};
+
+use common::{id, store};
+
+#[test]
+fn sums_up_the_effort_of_all_contributing_aims() {
+    let store = store(
+        &[("goal", Effort::Days(1)), ("a", Effort::Days(2)), ("b", Effort::Days(3)), ("c", Effort::Days(4))],
+        &[("a", "goal", 1), ("b", "goal", 1), ("c", "a", 1), ("c", "b", 1)],
+    );
+    let totals = subtree_effort(&store, &id("goal"), false, &EffortScale::default()).unwrap();
+    // c contributes over two paths but only counts once
+    assert_eq!(totals.contributing_aim_count, 3);
+    assert!(matches!(totals.contributing_effort, Effort::Days(9)));
+    assert!(matches!(totals.total_effort, Effort::Weeks(2)));
+    assert!(matches!(totals.own_effort, Effort::Days(1)));
+}
+
+#[test]
+fn weights_contributions_by_their_share() {
+    let store = store(
+        &[("goal", Effort::Days(1)), ("a", Effort::Days(4)), ("b", Effort::Days(4)), ("c", Effort::Days(4))],
+        &[("a", "goal", 3), ("b", "goal", 1), ("c", "a", 1)],
+    );
+    let shares = contributions(&store, &id("goal"), true).unwrap();
+    assert_eq!(shares[&id("a")].0, 0.75);
+    assert_eq!(shares[&id("b")].0, 0.25);
+    assert_eq!(shares[&id("c")].0, 0.75);
+
+    let totals = subtree_effort(&store, &id("goal"), true, &EffortScale::default()).unwrap();
+    // 0.75 * 4d + 0.25 * 4d + 0.75 * 4d = 7d
+    assert!(matches!(totals.contributing_effort, Effort::Days(7)));
+}
+
+#[test]
+fn passes_shares_through_many_diamonds() {
+    // 30 layers of two aims, each contributing to both aims of the layer above,
+    // so there are 2^30 paths down to the lowest layer
+    let mut store = MemoryStore::new();
+    store.insert_aim(id("goal"), Aim::new("goal", Effort::Days(1)));
+    let mut upper = vec![id("goal")];
+    for layer in 0..30 {
+        let lower: Vec<String> = (0..2).map(|index| format!("{}.{}", layer, index)).collect();
+        for contributing in lower.iter() {
+            store.insert_aim(contributing.clone(), Aim::new(contributing, Effort::Days(1)));
+            for receiving in upper.iter() {
+                store.connect(format!("{}->{}", contributing, receiving), Connection {
+                    contributing: contributing.clone(),
+                    receiving: receiving.clone(),
+                    contribution: 1,
+                });
+            }
+        }
+        upper = lower;
+    }
+
+    let shares = contributions(&store, &id("goal"), true).unwrap();
+    assert_eq!(shares.len(), 60);
+    assert!(shares.values().all(|(share, _)| (share - 0.5).abs() < 1e-9));
+    let totals = subtree_effort(&store, &id("goal"), true, &EffortScale::default()).unwrap();
+    assert!(matches!(totals.contributing_effort, Effort::Weeks(6)));
+    let unweighted = contributions(&store, &id("goal"), false).unwrap();
+    assert!(unweighted.values().all(|(share, _)| *share == 1.0));
+}
+
+#[test]
+fn does_not_loop_on_cycles() {
+    let store = store(
+        &[("goal", Effort::Days(1)), ("a", Effort::Days(1)), ("b", Effort::Days(1))],
+        &[("a", "goal", 1), ("b", "a", 1), ("a", "b", 1), ("goal", "b", 1)],
+    );
+    let shares = contributions(&store, &id("goal"), true).unwrap();
+    assert_eq!(shares.len(), 2);
+    assert!(shares.values().all(|(share, _)| *share <= 1.0));
+}
+
+#[test]
+fn counts_progress_towards_completion() {
+    let mut store = store(
+        &[("goal", Effort::Days(1)), ("half", Effort::Days(2)), ("done", Effort::Days(2)), ("dropped", Effort::Days(10))],
+        &[("half", "goal", 1), ("done", "goal", 1), ("dropped", "goal", 1)],
+    );
+    let mut half = store.aim(&id("half")).unwrap();
+    half.progress = 50;
+    store.insert_aim(id("half"), half);
+    let mut done = store.aim(&id("done")).unwrap();
+    done.status = AimStatus::Achieved;
+    store.insert_aim(id("done"), done);
+    let mut dropped = store.aim(&id("dropped")).unwrap();
+    dropped.status = AimStatus::Abandoned;
+    store.insert_aim(id("dropped"), dropped);
+
+    let completion = completion(&store, &id("goal"), &EffortScale::default()).unwrap();
+    assert_eq!(completion.completion, 75.0);
+    assert!(matches!(completion.contributing_effort, Effort::Days(4)));
+    assert!(matches!(completion.done_effort, Effort::Days(3)));
+}
+
+#[test]
+fn uses_the_own_progress_without_contributors() {
+    let mut store = store(&[("alone", Effort::Days(1))], &[]);
+    let mut alone = store.aim(&id("alone")).unwrap();
+    alone.progress = 40;
+    store.insert_aim(id("alone"), alone);
+    assert_eq!(completion(&store, &id("alone"), &EffortScale::default()).unwrap().completion, 40.0);
+}
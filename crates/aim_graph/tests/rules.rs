@@ -0,0 +1,93 @@
+extern crate aim_graph;
+
+use aim_graph::{
+    Aim,
+    AimStatus,
+    Connection,
+    Effort,
+    EffortScale,
+};
+use aim_graph::rules::{
+    validate_aim,
+    validate_connection,
+    validate_status_transition,
+    validate_timestamp,
+    MAX_CLOCK_SKEW_MS,
+    MAX_EFFORT_MINUTES,
+    MAX_TAG_COUNT,
+    MAX_TITLE_LENGTH,
+};
+
+fn aim() -> Aim {
+    Aim::new("learn rust", Effort::Weeks(2))
+}
+
+#[test]
+fn accepts_a_regular_aim() {
+    assert_eq!(validate_aim(&aim(), &EffortScale::default()), Ok(()));
+}
+
+#[test]
+fn rejects_bad_titles() {
+    let blank = Aim { title: "  ".into(), ..aim() };
+    assert!(validate_aim(&blank, &EffortScale::default()).is_err());
+    let long = Aim { title: "a".repeat(MAX_TITLE_LENGTH + 1), ..aim() };
+    assert!(validate_aim(&long, &EffortScale::default()).is_err());
+    let long_but_multibyte = Aim { title: "ä".repeat(MAX_TITLE_LENGTH), ..aim() };
+    assert_eq!(validate_aim(&long_but_multibyte, &EffortScale::default()), Ok(()));
+}
+
+#[test]
+fn rejects_bad_colors_tags_and_progress() {
+    let color = Aim { color: ['x', '0', '0', '0', '0', '0'], ..aim() };
+    assert!(validate_aim(&color, &EffortScale::default()).is_err());
+    let empty_tag = Aim { tags: vec![" ".into()], ..aim() };
+    assert!(validate_aim(&empty_tag, &EffortScale::default()).is_err());
+    let many_tags = Aim { tags: (0..=MAX_TAG_COUNT).map(|n| n.to_string()).collect(), ..aim() };
+    assert!(validate_aim(&many_tags, &EffortScale::default()).is_err());
+    let progress = Aim { progress: 101, ..aim() };
+    assert!(validate_aim(&progress, &EffortScale::default()).is_err());
+}
+
+#[test]
+fn allows_some_clock_skew() {
+    assert_eq!(validate_timestamp(1000, 1000), Ok(()));
+    assert_eq!(validate_timestamp(1000 + MAX_CLOCK_SKEW_MS, 1000), Ok(()));
+    assert!(validate_timestamp(1001 + MAX_CLOCK_SKEW_MS, 1000).is_err());
+    assert!(validate_timestamp(-1, 1000).is_err());
+}
+
+#[test]
+fn rejects_empty_and_self_connections() {
+    let connection = Connection {
+        contributing: "a",
+        receiving: "b",
+        contribution: 1,
+    };
+    assert_eq!(validate_connection(&connection), Ok(()));
+    assert!(validate_connection(&Connection { contribution: 0, ..connection.clone() }).is_err());
+    assert!(validate_connection(&Connection { receiving: "a", ..connection }).is_err());
+}
+
+#[test]
+fn finished_aims_can_only_be_reopened() {
+    let achieved = Aim { status: AimStatus::Achieved, ..aim() };
+    let abandoned = Aim { status: AimStatus::Abandoned, ..aim() };
+    let in_progress = Aim { status: AimStatus::InProgress, ..aim() };
+    assert_eq!(validate_status_transition(&aim(), &achieved), Ok(()));
+    assert_eq!(validate_status_transition(&achieved, &in_progress), Ok(()));
+    assert!(validate_status_transition(&achieved, &abandoned).is_err());
+    assert!(validate_status_transition(&abandoned, &in_progress).is_err());
+    assert_eq!(validate_status_transition(&abandoned, &aim()), Ok(()));
+}
+
+#[test]
+fn rejects_efforts_beyond_the_limit() {
+    let scale = EffortScale::default();
+    let limit = Aim { effort: Effort::Minutes(MAX_EFFORT_MINUTES), ..aim() };
+    assert_eq!(validate_aim(&limit, &scale), Ok(()));
+    let too_much = Aim { effort: Effort::Minutes(MAX_EFFORT_MINUTES + 1), ..aim() };
+    assert!(validate_aim(&too_much, &scale).is_err());
+    let overflowing = Aim { effort: Effort::Years(u64::MAX), ..aim() };
+    assert!(validate_aim(&overflowing, &scale).is_err());
+}
@@ -0,0 +1,73 @@
+extern crate aim_graph;
+
+mod common;
+
+use aim_graph::{
+    AimStore,
+    Connection,
+    Direction,
+    Effort,
+    NotFound,
+};
+use aim_graph::traversal::{
+    Edge,
+    collect_edges,
+};
+
+use common::{id, store};
+
+#[test]
+fn lists_connected_aims_in_both_directions() {
+    let store = store(
+        &[("root", Effort::Years(1)), ("a", Effort::Days(1)), ("b", Effort::Days(2))],
+        &[("a", "root", 1), ("b", "root", 3), ("b", "a", 1)],
+    );
+
+    let contributing: Vec<String> = store.connected(&id("root"), Direction::Contributing).unwrap()
+        .into_iter().map(|connected| connected.aim_id).collect();
+    assert_eq!(contributing, vec![id("a"), id("b")]);
+
+    let receiving = store.connected(&id("b"), Direction::Receiving).unwrap();
+    assert_eq!(receiving.len(), 2);
+    assert_eq!(receiving[0].connection_id, "b->root");
+    assert_eq!(receiving[0].contribution, 3);
+    assert_eq!(receiving[1].aim.title, "a");
+}
+
+#[test]
+fn reports_missing_aims() {
+    let store = store(&[("a", Effort::Days(1))], &[("a", "gone", 1)]);
+    assert_eq!(store.aim(&id("b")), Err(NotFound(id("b"))));
+    assert_eq!(store.connected(&id("a"), Direction::Receiving), Err(NotFound(id("gone"))));
+}
+
+#[test]
+fn replaces_and_removes_connections() {
+    let mut store = store(&[("a", Effort::Days(1)), ("b", Effort::Days(1))], &[("a", "b", 1)]);
+    store.connect(id("a->b"), Connection {
+        contributing: id("a"),
+        receiving: id("b"),
+        contribution: 5,
+    });
+    assert_eq!(store.connections().count(), 1);
+    assert_eq!(store.connected(&id("b"), Direction::Contributing).unwrap()[0].contribution, 5);
+
+    store.disconnect(&id("a->b"));
+    assert!(store.connected(&id("b"), Direction::Contributing).unwrap().is_empty());
+}
+
+#[test]
+fn collects_every_reachable_edge_once() {
+    let store = store(
+        &[("root", Effort::Years(1)), ("a", Effort::Days(1)), ("b", Effort::Days(1)), ("c", Effort::Days(1)), ("elsewhere", Effort::Days(1))],
+        &[("a", "root", 1), ("b", "root", 1), ("c", "a", 2), ("c", "b", 2), ("root", "c", 1), ("elsewhere", "elsewhere", 1)],
+    );
+    let edges = collect_edges(&store, &[id("root")]).unwrap();
+    assert_eq!(edges.len(), 5);
+    assert!(edges.contains(&Edge {
+        contributing: id("root"),
+        receiving: id("c"),
+        contribution: 1,
+    }));
+    assert!(edges.iter().all(|edge| edge.receiving != "elsewhere"));
+}
@@ -0,0 +1,159 @@
+//! Typed loading of app entries, shared by the zomes of this happ.
+//!
+//! Every loader reports what went wrong as a `LoadError`, and the batch variants
+//! keep going past entries that cannot be loaded, listing them as `LoadFailure`s.
+
+extern crate hdk;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+
+use std::convert::TryFrom;
+use std::fmt;
+
+use hdk::{
+    error::ZomeApiError,
+    prelude::LinkMatch,
+};
+use hdk::holochain_core_types::entry::Entry;
+
+use hdk::holochain_persistence_api::{
+    cas::content::Address,
+};
+
+use hdk::holochain_json_api::{
+    json::JsonString,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AddressAndEntry<T> {
+    pub entry: T,
+    pub address: Address,
+}
+
+/// Why an entry could not be loaded. Serializes with a stable `code`, like
+/// `{"code": "not_found", "address": "Qm..."}`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "code", rename_all = "snake_case")]
+pub enum LoadError {
+    /// There is no entry at the address, or it was deleted.
+    NotFound { address: Address },
+    /// The entry exists but is not of the expected type.
+    WrongEntryType { address: Address, expected: String },
+    /// The entry could not be read as the expected type.
+    Deserialize { address: Address, entry_type: String },
+    /// Holochain failed to get the entry or the links.
+    Internal { reason: String },
+}
+
+pub type LoadResult<T> = Result<T, LoadError>;
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::NotFound { address } => write!(f, "no entry at {}", address),
+            LoadError::WrongEntryType { address, expected } => write!(f, "the entry at {} is not a {}", address, expected),
+            LoadError::Deserialize { address, entry_type } => write!(f, "the {} at {} could not be read", entry_type, address),
+            LoadError::Internal { reason } => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl From<ZomeApiError> for LoadError {
+    fn from(error: ZomeApiError) -> LoadError {
+        LoadError::Internal { reason: error.to_string() }
+    }
+}
+
+impl From<LoadError> for ZomeApiError {
+    fn from(error: LoadError) -> ZomeApiError {
+        ZomeApiError::Internal(error.to_string())
+    }
+}
+
+/// An address a batch loader skipped, and why.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LoadFailure {
+    pub address: Address,
+    pub error: LoadError,
+}
+
+/// What a batch loader got: the entries it could load, and the addresses it could not.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Loaded<T> {
+    pub entries: Vec<AddressAndEntry<T>>,
+    pub failures: Vec<LoadFailure>,
+}
+
+impl<T> Default for Loaded<T> {
+    fn default() -> Loaded<T> {
+        Loaded {
+            entries: Vec::new(),
+            failures: Vec::new(),
+        }
+    }
+}
+
+fn parse_app_entry<T: TryFrom<JsonString>>(address: &Address, entry: Option<Entry>, expected: Option<&str>) -> LoadResult<T> {
+    match entry {
+        Some(Entry::App(app_entry_type, json_string)) => {
+            let entry_type = String::from(app_entry_type);
+            if let Some(expected) = expected {
+                if entry_type != expected {
+                    return Err(LoadError::WrongEntryType {
+                        address: address.clone(),
+                        expected: expected.into(),
+                    });
+                }
+            }
+            T::try_from(json_string).map_err(|_| LoadError::Deserialize {
+                address: address.clone(),
+                entry_type,
+            })
+        },
+        Some(_) => Err(LoadError::WrongEntryType {
+            address: address.clone(),
+            expected: expected.unwrap_or("app entry").into(),
+        }),
+        None => Err(LoadError::NotFound { address: address.clone() }),
+    }
+}
+
+/// Loads the latest version of the app entry at `address`, whatever its entry type.
+pub fn load_entry<T: TryFrom<JsonString>>(address: &Address) -> LoadResult<T> {
+    parse_app_entry(address, hdk::get_entry(address)?, None)
+}
+
+/// Loads the latest version of the app entry at `address`, checking that it is an
+/// `entry_type` before reading it.
+pub fn load_app_entry<T: TryFrom<JsonString>>(address: &Address, entry_type: &str) -> LoadResult<T> {
+    parse_app_entry(address, hdk::get_entry(address)?, Some(entry_type))
+}
+
+/// Loads every address, keeping the ones that fail to load apart.
+pub fn load_entries<T: TryFrom<JsonString>>(addresses: Vec<Address>) -> Loaded<T> {
+    let mut loaded = Loaded::default();
+    for address in addresses {
+        match load_entry(&address) {
+            Ok(entry) => loaded.entries.push(AddressAndEntry { entry, address }),
+            Err(error) => loaded.failures.push(LoadFailure { address, error }),
+        }
+    }
+    loaded
+}
+
+/// Loads the entries linked from `base`, failing if any of them cannot be loaded.
+pub fn load_linked<T: TryFrom<JsonString>>(base: &Address, link_type: &str) -> LoadResult<Vec<AddressAndEntry<T>>> {
+    let loaded = load_linked_entries(base, link_type)?;
+    match loaded.failures.into_iter().next() {
+        Some(failure) => Err(failure.error),
+        None => Ok(loaded.entries),
+    }
+}
+
+/// Loads the entries linked from `base`. Only getting the links themselves can fail;
+/// entries that cannot be loaded are listed in `failures`.
+pub fn load_linked_entries<T: TryFrom<JsonString>>(base: &Address, link_type: &str) -> LoadResult<Loaded<T>> {
+    let addresses = hdk::get_links(base, LinkMatch::Exactly(link_type), LinkMatch::Any)?.addresses();
+    Ok(load_entries(addresses))
+}
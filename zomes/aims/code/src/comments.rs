@@ -0,0 +1,380 @@
+use std::collections::HashMap;
+
+use aim_graph::comments::{
+    comment_deletion,
+    prune_deleted,
+    validate_comment_edit,
+    validate_comment_text,
+    CommentDeletion,
+    Thread,
+};
+
+use hdk::{
+    entry_definition::ValidatingEntryType,
+    prelude::LinkMatch,
+};
+use hdk::holochain_core_types::{
+    entry::Entry,
+    dna::entry_types::Sharing,
+};
+
+use hdk::holochain_persistence_api::{
+    cas::content::Address,
+};
+
+use hdk::holochain_json_api::{
+    error::JsonError,
+    json::JsonString,
+};
+
+use crate::{
+    AddressAndEntry,
+    errors::{
+        AddressError,
+        AimsError,
+        AimsResult,
+        load_app_entry,
+    },
+    get_entry_author,
+    initial_header,
+    profiles::{
+        get_profiles_of_agent,
+        Profile,
+    },
+    roles::original_entry_address,
+    validation::validate_author,
+};
+
+/// A comment on an aim or a connection, or a reply to another comment.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct Comment {
+    target: Address,
+    parent: Option<Address>,
+    author: Address,
+    text: String,
+    timestamp_ms: i64,
+    edited_at_ms: Option<i64>,
+    /// A deleted comment that had replies stays as a tombstone without text.
+    #[serde(default)]
+    deleted: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct CommentThread {
+    address: Address,
+    comment: Comment,
+    author_profiles: Vec<AddressAndEntry<Profile>>,
+    replies: Vec<CommentThread>,
+}
+
+impl Thread for CommentThread {
+    fn is_deleted(&self) -> bool {
+        self.comment.deleted
+    }
+
+    fn replies(&self) -> &[CommentThread] {
+        &self.replies
+    }
+
+    fn replies_mut(&mut self) -> &mut Vec<CommentThread> {
+        &mut self.replies
+    }
+}
+
+/// The comment threads of an aim or connection. Comments that could not be loaded
+/// are listed in `errors` and left out of `threads`, along with their replies;
+/// so are author profiles that could not be loaded.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct CommentList {
+    threads: Vec<CommentThread>,
+    errors: Vec<AddressError>,
+}
+
+fn load_comment(comment_address: &Address) -> AimsResult<Comment> {
+    load_app_entry(comment_address, "comment")
+}
+
+fn target_link_type(target_address: &Address) -> AimsResult<&'static str> {
+    match hdk::get_entry(target_address)? {
+        Some(Entry::App(entry_type, _)) => match String::from(entry_type).as_str() {
+            "aim" => Ok("has_comment"),
+            "connection" => Ok("has_connection_comment"),
+            _ => Err(AimsError::WrongEntryType {
+                address: target_address.clone(),
+                expected: "aim or connection".into(),
+            }),
+        },
+        Some(_) => Err(AimsError::WrongEntryType {
+            address: target_address.clone(),
+            expected: "aim or connection".into(),
+        }),
+        None => Err(AimsError::NotFound { address: target_address.clone() }),
+    }
+}
+
+/// Reweighting a connection makes a new version of it. Its comments stay with the
+/// first version, so they are found from whichever version is given.
+fn comment_target(target_address: &Address, link_type: &str) -> AimsResult<Address> {
+    if link_type == "has_connection_comment" {
+        original_entry_address(&initial_header(target_address)?)
+    } else {
+        Ok(target_address.clone())
+    }
+}
+
+/// Comments on an aim or a connection. Given a `parent_comment_address`, the
+/// comment is a reply to that comment instead of a top level comment.
+pub fn handle_add_comment(
+    target_address: Address,
+    parent_comment_address: Option<Address>,
+    text: String,
+    timestamp_ms: i64,
+) -> AimsResult<Address> {
+    let link_type = target_link_type(&target_address)?;
+    let target_address = comment_target(&target_address, link_type)?;
+    let comment = Comment {
+        target: target_address.clone(),
+        parent: parent_comment_address.clone(),
+        author: hdk::AGENT_ADDRESS.clone(),
+        text,
+        timestamp_ms,
+        edited_at_ms: None,
+        deleted: false,
+    };
+    let entry = Entry::App("comment".into(), comment.into());
+    let comment_address = hdk::commit_entry(&entry)?;
+    match parent_comment_address {
+        Some(parent) => hdk::link_entries(&parent, &comment_address, "has_reply", "")?,
+        None => hdk::link_entries(&target_address, &comment_address, link_type, "")?,
+    };
+    Ok(comment_address)
+}
+
+/// Edits a comment as a new version of it, so earlier versions stay in its history.
+pub fn handle_edit_comment(comment_address: Address, text: String, timestamp_ms: i64) -> AimsResult<Address> {
+    let comment = load_comment(&comment_address)?;
+    if comment.deleted {
+        return Err(AimsError::conflict("this comment was deleted"));
+    }
+    let comment = Comment {
+        text,
+        edited_at_ms: Some(timestamp_ms),
+        ..comment
+    };
+    let entry = Entry::App("comment".into(), comment.into());
+    Ok(hdk::update_entry(entry, &comment_address)?)
+}
+
+/// Deletes a comment. A comment that has replies is replaced by a tombstone
+/// without text instead, so the replies stay in their thread; tombstones are
+/// left out of the threads once all their replies are gone.
+pub fn handle_delete_comment(comment_address: Address) -> AimsResult<()> {
+    let comment = load_comment(&comment_address)?;
+    let reply_count = hdk::get_links(
+        &comment_address,
+        LinkMatch::Exactly("has_reply"),
+        LinkMatch::Any
+    )?.addresses().len();
+    if comment_deletion(reply_count) == CommentDeletion::Tombstone {
+        if !comment.deleted {
+            let tombstone = Comment {
+                text: String::new(),
+                deleted: true,
+                ..comment
+            };
+            hdk::update_entry(Entry::App("comment".into(), tombstone.into()), &comment_address)?;
+        }
+        return Ok(());
+    }
+    match &comment.parent {
+        Some(parent) => hdk::remove_link(parent, &comment_address, "has_reply", "")?,
+        None => hdk::remove_link(&comment.target, &comment_address, target_link_type(&comment.target)?, "")?,
+    };
+    hdk::remove_entry(&comment_address)?;
+    Ok(())
+}
+
+fn load_threads(
+    base: &Address,
+    link_type: &str,
+    profiles: &mut HashMap<Address, Vec<AddressAndEntry<Profile>>>,
+    errors: &mut Vec<AddressError>,
+) -> AimsResult<Vec<CommentThread>> {
+    let mut threads = Vec::new();
+    for address in hdk::get_links(base, LinkMatch::Exactly(link_type), LinkMatch::Any)?.addresses() {
+        let comment = match load_comment(&address) {
+            Ok(comment) => comment,
+            Err(error) => {
+                errors.push(AddressError { address, error });
+                continue;
+            },
+        };
+        if !profiles.contains_key(&comment.author) {
+            profiles.insert(comment.author.clone(), get_profiles_of_agent(&comment.author, errors)?);
+        }
+        threads.push(CommentThread {
+            author_profiles: profiles[&comment.author].clone(),
+            replies: load_threads(&address, "has_reply", profiles, errors)?,
+            address,
+            comment,
+        });
+    }
+    threads.sort_by_key(|thread| thread.comment.timestamp_ms);
+    Ok(threads)
+}
+
+/// Returns the top level comments of an aim or connection, oldest first, each
+/// with all its replies. Pagination applies to the top level comments.
+pub fn handle_get_comments(
+    target_address: Address,
+    offset: Option<u32>,
+    limit: Option<u32>,
+) -> AimsResult<CommentList> {
+    let link_type = target_link_type(&target_address)?;
+    let mut profiles = HashMap::new();
+    let mut errors = Vec::new();
+    let target_address = comment_target(&target_address, link_type)?;
+    let mut threads = load_threads(&target_address, link_type, &mut profiles, &mut errors)?;
+    prune_deleted(&mut threads);
+    let threads = threads.into_iter()
+        .skip(offset.unwrap_or(0) as usize)
+        .take(limit.map_or(usize::MAX, |limit| limit as usize))
+        .collect();
+    Ok(CommentList {
+        threads,
+        errors,
+    })
+}
+
+fn validate_comment(comment: &Comment, sources: &[Address]) -> Result<(), String> {
+    if !sources.contains(&comment.author) {
+        return Err("a comment has to be committed by its author".into());
+    }
+    validate_comment_text(&comment.text, comment.deleted)
+}
+
+/// A reply belongs to the same aim or connection as the comment it replies to.
+fn validate_reply_target(comment: &Comment) -> Result<(), String> {
+    if let Some(parent_address) = &comment.parent {
+        let parent = load_comment(parent_address)
+            .map_err(|_| String::from("could not load the comment replied to"))?;
+        if parent.target != comment.target {
+            return Err("a reply must have the same target as the comment it replies to".into());
+        }
+    }
+    Ok(())
+}
+
+/// Its author links a comment from its target, or a reply from its parent.
+fn validate_comment_link(base: &Address, comment_address: &Address, reply: bool, sources: &[Address]) -> Result<(), String> {
+    let comment = load_comment(comment_address)
+        .map_err(|_| String::from("could not load the linked comment"))?;
+    if !sources.contains(&comment.author) {
+        return Err("only the author of a comment can link it".into());
+    }
+    let linked_from_its_place = match &comment.parent {
+        Some(parent) => reply && parent == base,
+        None => !reply && comment.target == *base,
+    };
+    if linked_from_its_place {
+        Ok(())
+    } else if reply {
+        Err("a reply can only be linked from the comment it replies to".into())
+    } else {
+        Err("a comment can only be linked from its target".into())
+    }
+}
+
+/// Only the author of a comment can take it out of its thread.
+fn validate_comment_link_removal(comment_address: &Address, sources: &[Address]) -> Result<(), String> {
+    let author = get_entry_author(comment_address)
+        .map_err(|_| String::from("could not determine the author of the comment"))?;
+    if sources.contains(&author) {
+        Ok(())
+    } else {
+        Err("only the author of a comment can remove it".into())
+    }
+}
+
+pub fn comment_entry_definition() -> ValidatingEntryType {
+    entry!(
+        name: "comment",
+        description: "a comment on an aim or a connection, or a reply to another comment",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: | validation_data: hdk::EntryValidationData<Comment>| {
+            match validation_data {
+                hdk::EntryValidationData::Create { entry, validation_data } => {
+                    validate_comment(&entry, &validation_data.sources())?;
+                    validate_reply_target(&entry)
+                },
+                hdk::EntryValidationData::Modify { new_entry, old_entry, old_entry_header, validation_data } => {
+                    validate_author(&old_entry_header, &validation_data.sources())?;
+                    if new_entry.author != old_entry.author || new_entry.target != old_entry.target || new_entry.parent != old_entry.parent {
+                        return Err("only the text of a comment can be edited".into());
+                    }
+                    validate_comment(&new_entry, &validation_data.sources())?;
+                    validate_comment_edit(old_entry.deleted, &new_entry.text, new_entry.deleted)
+                },
+                hdk::EntryValidationData::Delete { old_entry_header, validation_data, .. } => {
+                    validate_author(&old_entry_header, &validation_data.sources())
+                }
+            }
+        },
+        links: [
+            from!(
+                "aim",
+                link_type: "has_comment",
+                validation_package:  || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: | validation_data: hdk::LinkValidationData | {
+                    match validation_data {
+                        hdk::LinkValidationData::LinkAdd { link, validation_data } => {
+                            validate_comment_link(link.link.base(), link.link.target(), false, &validation_data.sources())
+                        },
+                        hdk::LinkValidationData::LinkRemove { link, validation_data } => {
+                            validate_comment_link_removal(link.link.target(), &validation_data.sources())
+                        }
+                    }
+                }
+            ),
+            from!(
+                "connection",
+                link_type: "has_connection_comment",
+                validation_package:  || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: | validation_data: hdk::LinkValidationData | {
+                    match validation_data {
+                        hdk::LinkValidationData::LinkAdd { link, validation_data } => {
+                            validate_comment_link(link.link.base(), link.link.target(), false, &validation_data.sources())
+                        },
+                        hdk::LinkValidationData::LinkRemove { link, validation_data } => {
+                            validate_comment_link_removal(link.link.target(), &validation_data.sources())
+                        }
+                    }
+                }
+            ),
+            from!(
+                "comment",
+                link_type: "has_reply",
+                validation_package:  || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: | validation_data: hdk::LinkValidationData | {
+                    match validation_data {
+                        hdk::LinkValidationData::LinkAdd { link, validation_data } => {
+                            validate_comment_link(link.link.base(), link.link.target(), true, &validation_data.sources())
+                        },
+                        hdk::LinkValidationData::LinkRemove { link, validation_data } => {
+                            validate_comment_link_removal(link.link.target(), &validation_data.sources())
+                        }
+                    }
+                }
+            )
+        ]
+    )
+}
@@ -0,0 +1,279 @@
+use hdk::{
+    entry_definition::ValidatingEntryType,
+    prelude::LinkMatch,
+};
+use hdk::holochain_core_types::{
+    entry::Entry,
+    dna::entry_types::Sharing,
+    signature::{
+        Provenance,
+        Signature,
+    },
+};
+
+use hdk::holochain_persistence_api::{
+    cas::content::Address,
+};
+
+use hdk::holochain_json_api::{
+    error::JsonError,
+    json::JsonString,
+};
+
+use std::convert::TryFrom;
+
+use crate::{
+    Connection,
+    CreatedConnection,
+    commit_connection,
+    duplicates::MergePolicy,
+    errors::{
+        AddressError,
+        AimsError,
+        AimsResult,
+        load_app_entry,
+    },
+    roles::can_maintain,
+    signals::notify_proposal,
+};
+
+/// A request to connect a contributing aim to an aim of another agent.
+/// It only becomes a connection once the author of the receiving aim accepts it.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct ConnectionProposal {
+    pub contributing: Address,
+    pub receiving: Address,
+    pub contribution: u32,
+    pub proposer: Address,
+    pub timestamp_ms: i64,
+}
+
+/// The signature of an owner or maintainer of the receiving aim over the address of
+/// a proposal.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+pub struct Consent {
+    pub proposal: Address,
+    pub signer: Address,
+    pub signature: Signature,
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct ProposalWithAddress {
+    proposal: ConnectionProposal,
+    proposal_address: Address,
+}
+
+pub fn handle_propose_connection(
+    contributing_aim_address: Address,
+    receiving_aim_address: Address,
+    contribution: u32,
+    timestamp_ms: i64,
+) -> AimsResult<Address> {
+    let proposal = ConnectionProposal {
+        contributing: contributing_aim_address,
+        receiving: receiving_aim_address.clone(),
+        contribution,
+        proposer: hdk::AGENT_ADDRESS.clone(),
+        timestamp_ms,
+    };
+    let entry = Entry::App("connection_proposal".into(), proposal.into());
+    let proposal_address = hdk::commit_entry(&entry)?;
+    hdk::link_entries(&receiving_aim_address, &proposal_address, "has_connection_proposal", "")?;
+    notify_proposal(&proposal_address, &receiving_aim_address)?;
+    Ok(proposal_address)
+}
+
+/// The open proposals of an aim, with the ones that could not be loaded listed in
+/// `errors`.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct ProposalList {
+    proposals: Vec<ProposalWithAddress>,
+    errors: Vec<AddressError>,
+}
+
+pub fn handle_get_connection_proposals(aim_address: Address) -> AimsResult<ProposalList> {
+    let mut list = ProposalList {
+        proposals: Vec::new(),
+        errors: Vec::new(),
+    };
+    for proposal_address in hdk::get_links(
+        &aim_address,
+        LinkMatch::Exactly("has_connection_proposal"),
+        LinkMatch::Any
+    )?.addresses() {
+        match load_proposal(&proposal_address) {
+            Ok(proposal) => list.proposals.push(ProposalWithAddress {
+                proposal,
+                proposal_address,
+            }),
+            Err(error) => list.errors.push(AddressError {
+                address: proposal_address,
+                error,
+            }),
+        }
+    }
+    Ok(list)
+}
+
+/// Signs the proposal and commits the connection it describes. Only owners and
+/// maintainers of the receiving aim can accept a proposal. If the aims got connected
+/// in the meantime, the existing connection is kept.
+pub fn handle_accept_connection_proposal(proposal_address: Address) -> AimsResult<CreatedConnection> {
+    let proposal = load_proposal(&proposal_address)?;
+    if !can_maintain(&proposal.receiving, &hdk::AGENT_ADDRESS)? {
+        return Err(AimsError::unauthorized("only owners and maintainers of the receiving aim can accept this proposal"));
+    }
+    let signature = hdk::sign(String::from(proposal_address.clone()))?;
+    let connection = Connection {
+        contributing: proposal.contributing.clone(),
+        receiving: proposal.receiving.clone(),
+        contribution: proposal.contribution,
+        consent: Some(Consent {
+            proposal: proposal_address.clone(),
+            signer: hdk::AGENT_ADDRESS.clone(),
+            signature,
+        }),
+    };
+    let created = commit_connection(connection, MergePolicy::default())?;
+    hdk::remove_link(&proposal.receiving, &proposal_address, "has_connection_proposal", "")?;
+    Ok(created)
+}
+
+/// Declining only hides the proposal from the receiving aim; ignoring a proposal
+/// is equally valid, it just never turns into a connection.
+pub fn handle_decline_connection_proposal(proposal_address: Address) -> AimsResult<()> {
+    let proposal = load_proposal(&proposal_address)?;
+    if !can_maintain(&proposal.receiving, &hdk::AGENT_ADDRESS)? {
+        return Err(AimsError::unauthorized("only owners and maintainers of the receiving aim can decline this proposal"));
+    }
+    hdk::remove_link(&proposal.receiving, &proposal_address, "has_connection_proposal", "")?;
+    Ok(())
+}
+
+pub fn load_proposal(proposal_address: &Address) -> AimsResult<ConnectionProposal> {
+    load_app_entry(proposal_address, "connection_proposal")
+}
+
+/// A connection may only point into an aim the linking agent does not maintain if it
+/// carries a valid signature of an owner or maintainer of that aim over a proposal
+/// describing exactly this connection.
+pub fn validate_connection_consent(connection_address: &Address, link_author: &Address) -> Result<(), String> {
+    let connection = match hdk::get_entry(connection_address) {
+        Ok(Some(Entry::App(_, json_string))) => Connection::try_from(json_string)
+            .map_err(|_| String::from("linked entry is not a connection"))?,
+        _ => return Err("could not load the linked connection".into()),
+    };
+    let may_connect = |agent: &Address| can_maintain(&connection.receiving, agent)
+        .map_err(|_| String::from("could not determine the role on the receiving aim"));
+    if may_connect(link_author)? {
+        return Ok(());
+    }
+    let consent = connection.consent
+        .ok_or_else(|| String::from("connecting to an aim of another agent requires their consent"))?;
+    if !may_connect(&consent.signer)? {
+        return Err("consent was not given by an owner or maintainer of the receiving aim".into());
+    }
+    let proposal = load_proposal(&consent.proposal)
+        .map_err(|_| String::from("could not load the consented proposal"))?;
+    if proposal.contributing != connection.contributing
+        || proposal.receiving != connection.receiving
+        || proposal.contribution != connection.contribution {
+        return Err("connection does not match the consented proposal".into());
+    }
+    let provenance = Provenance::new(consent.signer, consent.signature);
+    match hdk::verify_signature(provenance, String::from(consent.proposal)) {
+        Ok(true) => Ok(()),
+        _ => Err("invalid consent signature".into()),
+    }
+}
+
+/// A proposal can be withdrawn by its proposer, and declined or accepted by the
+/// owners and maintainers of the receiving aim.
+fn validate_proposal_removal(proposal: &ConnectionProposal, sources: &[Address]) -> Result<(), String> {
+    if sources.contains(&proposal.proposer) {
+        return Ok(());
+    }
+    for source in sources {
+        let may_maintain = can_maintain(&proposal.receiving, source)
+            .map_err(|_| String::from("could not determine the role on the receiving aim"))?;
+        if may_maintain {
+            return Ok(());
+        }
+    }
+    Err("only the proposer and maintainers of the receiving aim can remove a proposal".into())
+}
+
+/// Only the proposer changes a proposal, and it stays a proposal to the same aim.
+fn validate_proposal_update(old_proposal: &ConnectionProposal, new_proposal: &ConnectionProposal, sources: &[Address]) -> Result<(), String> {
+    if !sources.contains(&old_proposal.proposer) {
+        return Err("only the proposer can change a proposal".into());
+    }
+    if new_proposal.proposer != old_proposal.proposer || new_proposal.receiving != old_proposal.receiving {
+        return Err("the proposer and the receiving aim of a proposal cannot be changed".into());
+    }
+    Ok(())
+}
+
+/// Proposals hang off the aim they propose to. The proposer links them, and the
+/// same agents that can remove a proposal can unlink it.
+fn validate_proposal_link(base: &Address, proposal_address: &Address, remove: bool, sources: &[Address]) -> Result<(), String> {
+    let proposal = load_proposal(proposal_address)
+        .map_err(|_| String::from("could not load the linked proposal"))?;
+    if *base != proposal.receiving {
+        return Err("a proposal can only be linked from the aim it proposes to".into());
+    }
+    if remove {
+        validate_proposal_removal(&proposal, sources)
+    } else if sources.contains(&proposal.proposer) {
+        Ok(())
+    } else {
+        Err("only the proposer can link a proposal".into())
+    }
+}
+
+pub fn connection_proposal_entry_definition() -> ValidatingEntryType {
+    entry!(
+        name: "connection_proposal",
+        description: "a request to let an aim contribute to an aim of another agent",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: | validation_data: hdk::EntryValidationData<ConnectionProposal>| {
+            match validation_data {
+                hdk::EntryValidationData::Create { entry, validation_data } => {
+                    if validation_data.sources().contains(&entry.proposer) {
+                        Ok(())
+                    } else {
+                        Err("a proposal has to be committed by its proposer".into())
+                    }
+                },
+                hdk::EntryValidationData::Modify { new_entry, old_entry, validation_data, .. } => {
+                    validate_proposal_update(&old_entry, &new_entry, &validation_data.sources())
+                },
+                hdk::EntryValidationData::Delete { old_entry, validation_data, .. } => {
+                    validate_proposal_removal(&old_entry, &validation_data.sources())
+                }
+            }
+        },
+        links: [
+            from!(
+                "aim",
+                link_type: "has_connection_proposal",
+                validation_package:  || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: | validation_data: hdk::LinkValidationData | {
+                    match validation_data {
+                        hdk::LinkValidationData::LinkAdd { link, validation_data } => {
+                            validate_proposal_link(link.link.base(), link.link.target(), false, &validation_data.sources())
+                        },
+                        hdk::LinkValidationData::LinkRemove { link, validation_data } => {
+                            validate_proposal_link(link.link.base(), link.link.target(), true, &validation_data.sources())
+                        }
+                    }
+                }
+            )
+        ]
+    )
+}
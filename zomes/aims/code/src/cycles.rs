@@ -0,0 +1,203 @@
+use std::collections::{HashMap, HashSet};
+
+use hdk::{
+    prelude::LinkMatch,
+};
+
+use hdk::holochain_persistence_api::{
+    cas::content::Address,
+};
+
+use hdk::holochain_json_api::{
+    error::JsonError,
+    json::JsonString,
+};
+
+use crate::{
+    errors::{
+        AimsError,
+        AimsResult,
+    },
+    get_entry_author,
+    properties::{
+        cycle_policy,
+        CyclePolicy,
+    },
+};
+
+/// Upper bound of aims visited while looking for a path, so a connection can
+/// always be created in bounded time.
+pub const MAX_CYCLE_SEARCH_AIMS: usize = 1000;
+
+/// The aims the given aim directly contributes to, read from links only.
+pub fn get_receiving_aim_addresses(aim_address: &Address) -> AimsResult<Vec<Address>> {
+    let mut receiving = Vec::new();
+    for connection_address in hdk::get_links(
+        aim_address,
+        LinkMatch::Exactly("contributes_to_connection"),
+        LinkMatch::Any
+    )?.addresses() {
+        receiving.extend(hdk::get_links(
+            &connection_address,
+            LinkMatch::Exactly("contributes_to_aim"),
+            LinkMatch::Any
+        )?.addresses());
+    }
+    Ok(receiving)
+}
+
+/// The outcome of looking for a chain of contributions between two aims.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathSearch {
+    /// The aims on the chain, starting with `from` and ending with `to`.
+    Found(Vec<Address>),
+    NotFound,
+    /// The search visited `MAX_CYCLE_SEARCH_AIMS` aims without finding a chain, so
+    /// there might still be one.
+    Incomplete,
+}
+
+/// Searches a chain of contributions leading from `from` to `to`.
+pub fn find_contribution_path(from: &Address, to: &Address) -> AimsResult<PathSearch> {
+    let mut visited = HashSet::new();
+    let mut stack = vec![vec![from.clone()]];
+    while let Some(path) = stack.pop() {
+        let current = path.last().unwrap().clone();
+        if current == *to {
+            return Ok(PathSearch::Found(path));
+        }
+        if !visited.insert(current.clone()) {
+            continue;
+        }
+        if visited.len() > MAX_CYCLE_SEARCH_AIMS {
+            return Ok(PathSearch::Incomplete);
+        }
+        for next in get_receiving_aim_addresses(&current)? {
+            if !visited.contains(&next) {
+                let mut next_path = path.clone();
+                next_path.push(next);
+                stack.push(next_path);
+            }
+        }
+    }
+    Ok(PathSearch::NotFound)
+}
+
+/// Why a connection went through although it might close a cycle.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+#[serde(tag = "code", rename_all = "snake_case")]
+pub enum CycleWarning {
+    /// The connection closes a cycle through these aims.
+    Cycle { aims: Vec<Address> },
+    /// The search gave up after this many aims, so a cycle could not be ruled out.
+    SearchIncomplete { searched_aims: u32 },
+}
+
+/// Checks whether connecting `contributing` to `receiving` would close a cycle and
+/// applies the DNA's cycle policy to it. A cycle the policy lets through is
+/// returned as a warning. If the search cannot finish, only the `warn` policy lets
+/// the connection through.
+pub fn check_connection_cycle(contributing: &Address, receiving: &Address) -> AimsResult<Option<CycleWarning>> {
+    let cycle = match find_contribution_path(receiving, contributing)? {
+        PathSearch::Found(cycle) => cycle,
+        PathSearch::NotFound => return Ok(None),
+        PathSearch::Incomplete => return match cycle_policy() {
+            CyclePolicy::Warn => Ok(Some(CycleWarning::SearchIncomplete {
+                searched_aims: MAX_CYCLE_SEARCH_AIMS as u32,
+            })),
+            _ => Err(AimsError::conflict(format!(
+                "could not rule out a cycle within {} aims", MAX_CYCLE_SEARCH_AIMS
+            ))),
+        },
+    };
+    match cycle_policy() {
+        CyclePolicy::Reject => Err(AimsError::conflict("this connection would create a cycle")),
+        CyclePolicy::Warn => Ok(Some(CycleWarning::Cycle { aims: cycle })),
+        CyclePolicy::AllowAcrossAgents => {
+            let mut authors = HashSet::new();
+            for aim_address in cycle.iter() {
+                authors.insert(get_entry_author(aim_address)?);
+            }
+            if authors.len() > 1 {
+                Ok(Some(CycleWarning::Cycle { aims: cycle }))
+            } else {
+                Err(AimsError::conflict("this connection would create a cycle among the aims of one agent"))
+            }
+        }
+    }
+}
+
+/// Cycles found among the aims of an agent.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct FoundCycles {
+    cycles: Vec<Vec<Address>>,
+    /// The search stopped after `MAX_CYCLE_SEARCH_AIMS` aims, the aims beyond were
+    /// not searched.
+    incomplete: bool,
+}
+
+/// Lists cycles reachable from the aims of this agent, each once, as the list of
+/// aims on it. The list is not exhaustive: aims are searched only once, so a
+/// cycle that runs through aims already searched from elsewhere can be missed.
+/// An empty list means there are no cycles, unless `incomplete` is set.
+pub fn handle_find_cycles() -> AimsResult<FoundCycles> {
+    let mut cycles: Vec<Vec<Address>> = Vec::new();
+    let mut incomplete = false;
+    let mut seen: HashSet<Vec<Address>> = HashSet::new();
+    let mut finished: HashSet<Address> = HashSet::new();
+    let mut neighbours: HashMap<Address, Vec<Address>> = HashMap::new();
+
+    for start in hdk::get_links(
+        &hdk::AGENT_ADDRESS,
+        LinkMatch::Exactly("created_aim"),
+        LinkMatch::Any
+    )?.addresses() {
+        if finished.contains(&start) {
+            continue;
+        }
+        // depth first search with an explicit stack of (aim, index of next neighbour)
+        let mut path: Vec<Address> = vec![start.clone()];
+        let mut indices: Vec<usize> = vec![0];
+        while let Some(current) = path.last().cloned() {
+            if !neighbours.contains_key(&current) {
+                neighbours.insert(current.clone(), get_receiving_aim_addresses(&current)?);
+            }
+            let index = *indices.last().unwrap();
+            match neighbours[&current].get(index).cloned() {
+                Some(next) => {
+                    *indices.last_mut().unwrap() += 1;
+                    if let Some(position) = path.iter().position(|aim| *aim == next) {
+                        let cycle = path[position..].to_vec();
+                        if seen.insert(canonical_rotation(&cycle)) {
+                            cycles.push(cycle);
+                        }
+                    } else if !finished.contains(&next) {
+                        if finished.len() < MAX_CYCLE_SEARCH_AIMS {
+                            path.push(next);
+                            indices.push(0);
+                        } else {
+                            incomplete = true;
+                        }
+                    }
+                },
+                None => {
+                    finished.insert(current);
+                    path.pop();
+                    indices.pop();
+                }
+            }
+        }
+    }
+    Ok(FoundCycles {
+        cycles,
+        incomplete,
+    })
+}
+
+fn canonical_rotation(cycle: &[Address]) -> Vec<Address> {
+    let smallest = cycle.iter().enumerate()
+        .min_by_key(|(_, address)| String::from((*address).clone()))
+        .map(|(index, _)| index)
+        .unwrap_or(0);
+    cycle[smallest..].iter().chain(cycle[..smallest].iter()).cloned().collect()
+}
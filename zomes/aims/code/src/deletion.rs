@@ -0,0 +1,227 @@
+use hdk::{
+    prelude::LinkMatch,
+};
+use hdk::holochain_core_types::chain_header::ChainHeader;
+
+use hdk::holochain_persistence_api::{
+    cas::content::Address,
+};
+
+use crate::{
+    Aim,
+    Connection,
+    anchor_address,
+    commit_anchor,
+    errors::{
+        AimsError,
+        AimsResult,
+        load_app_entry,
+    },
+    get_aim,
+    get_entry_author,
+    properties::{
+        deletion_policy,
+        DeletionPolicy,
+    },
+    roles::remove_memberships,
+    root_aims_anchor_address,
+    signals::{
+        signal_aim_deleted,
+        signal_connection_deleted,
+    },
+    tags::unlink_tags,
+};
+
+pub const ARCHIVED_AIMS_ANCHOR: &str = "archived_aims";
+
+pub fn archived_aims_anchor_address() -> AimsResult<Address> {
+    anchor_address(ARCHIVED_AIMS_ANCHOR)
+}
+
+pub fn is_archived(aim_address: &Address) -> AimsResult<bool> {
+    Ok(!hdk::get_links(
+        aim_address,
+        LinkMatch::Exactly("is_archived"),
+        LinkMatch::Any
+    )?.addresses().is_empty())
+}
+
+/// Whether this agent may delete the aim under the configured deletion policy.
+/// Archiving hides an aim from everyone, so it follows the same rule.
+fn ensure_can_delete(aim_address: &Address) -> AimsResult<()> {
+    match deletion_policy() {
+        DeletionPolicy::Anyone => Ok(()),
+        DeletionPolicy::Author => if get_entry_author(aim_address)? == *hdk::AGENT_ADDRESS {
+            Ok(())
+        } else {
+            Err(AimsError::unauthorized("only the author may delete or archive this aim"))
+        },
+    }
+}
+
+/// Archived aims are hidden from `get_aims` and from traversals but stay on the DHT
+/// together with their connections, so they can be brought back at any time.
+pub fn handle_archive_aim(aim_address: Address) -> AimsResult<()> {
+    ensure_can_delete(&aim_address)?;
+    if is_archived(&aim_address)? {
+        return Ok(());
+    }
+    hdk::link_entries(&aim_address, &commit_anchor(ARCHIVED_AIMS_ANCHOR)?, "is_archived", "")?;
+    Ok(())
+}
+
+pub fn handle_unarchive_aim(aim_address: Address) -> AimsResult<()> {
+    ensure_can_delete(&aim_address)?;
+    hdk::remove_link(&aim_address, &archived_aims_anchor_address()?, "is_archived", "")?;
+    Ok(())
+}
+
+pub fn handle_get_archived_aims() -> AimsResult<Vec<Address>> {
+    let mut archived = Vec::new();
+    for aim_address in hdk::get_links(
+        &hdk::AGENT_ADDRESS,
+        LinkMatch::Exactly("created_aim"),
+        LinkMatch::Any
+    )?.addresses() {
+        if is_archived(&aim_address)? {
+            archived.push(aim_address);
+        }
+    }
+    Ok(archived)
+}
+
+/// Removes a connection entry together with the four links written when it was created.
+pub fn handle_delete_connection(connection_address: Address) -> AimsResult<()> {
+    let connection: Connection = load_app_entry(&connection_address, "connection")?;
+
+    hdk::remove_link(&connection.contributing, &connection_address, "contributes_to_connection", "")?;
+    hdk::remove_link(&connection_address, &connection.receiving, "contributes_to_aim", "")?;
+    hdk::remove_link(&connection.receiving, &connection_address, "receives_from_connection", "")?;
+    hdk::remove_link(&connection_address, &connection.contributing, "receives_from_aim", "")?;
+
+    hdk::remove_entry(&connection_address)?;
+    signal_connection_deleted(&connection_address, &connection);
+    Ok(())
+}
+
+/// Removes the links that lead to a deleted aim besides its connections: the
+/// author's `created_aim` and root aim links, the archive, the tag index and the
+/// memberships. They belong to the author, so an aim deleted by someone else would
+/// leave them dangling otherwise.
+fn unlink_aim(aim_address: &Address, aim: &Aim, author: &Address) -> AimsResult<()> {
+    if is_archived(aim_address)? {
+        hdk::remove_link(aim_address, &archived_aims_anchor_address()?, "is_archived", "")?;
+    }
+    hdk::remove_link(author, aim_address, "created_aim", "")?;
+    let root_aims = hdk::get_links(author, LinkMatch::Exactly("has_root_aim"), LinkMatch::Any)?.addresses();
+    if root_aims.contains(aim_address) {
+        hdk::remove_link(author, aim_address, "has_root_aim", "")?;
+        hdk::remove_link(&root_aims_anchor_address()?, aim_address, "root_aim", "")?;
+    }
+    unlink_tags(aim_address, &aim.tags)?;
+    remove_memberships(aim_address)?;
+    Ok(())
+}
+
+/// Deletes an aim. If connections still point to or from it, the deletion is refused
+/// unless `cascade` is set, in which case these connections are deleted first.
+pub fn handle_delete_aim(aim_address: Address, cascade: bool) -> AimsResult<()> {
+    ensure_can_delete(&aim_address)?;
+    let mut connection_addresses = hdk::get_links(
+        &aim_address,
+        LinkMatch::Exactly("contributes_to_connection"),
+        LinkMatch::Any
+    )?.addresses();
+    connection_addresses.extend(hdk::get_links(
+        &aim_address,
+        LinkMatch::Exactly("receives_from_connection"),
+        LinkMatch::Any
+    )?.addresses());
+
+    if !connection_addresses.is_empty() && !cascade {
+        return Err(AimsError::conflict("this aim still has connections, delete them first or cascade"));
+    }
+    for connection_address in connection_addresses {
+        handle_delete_connection(connection_address)?;
+    }
+
+    let aim = get_aim(&aim_address)?;
+    let author = get_entry_author(&aim_address)?;
+    // the links go after the entry, so their validation can see that it is deleted
+    hdk::remove_entry(&aim_address)?;
+    unlink_aim(&aim_address, &aim, &author)?;
+    signal_aim_deleted(&aim_address);
+    Ok(())
+}
+
+/// Checks a deletion against the configured deletion policy.
+pub fn validate_deletion(old_entry_header: &ChainHeader, sources: &[Address]) -> Result<(), String> {
+    match deletion_policy() {
+        DeletionPolicy::Anyone => Ok(()),
+        DeletionPolicy::Author => {
+            let is_author = old_entry_header.provenances().iter()
+                .any(|provenance| sources.contains(&provenance.source()));
+            if is_author {
+                Ok(())
+            } else {
+                Err("only the author may delete this entry".into())
+            }
+        }
+    }
+}
+
+/// Whether one of `sources` may delete an aim by `author` under the configured deletion policy.
+fn may_delete_aim(author: &Address, sources: &[Address]) -> bool {
+    match deletion_policy() {
+        DeletionPolicy::Anyone => true,
+        DeletionPolicy::Author => sources.contains(author),
+    }
+}
+
+fn aim_author(aim_address: &Address) -> Result<Address, String> {
+    get_entry_author(aim_address)
+        .map_err(|_| String::from("could not determine the author of the aim"))
+}
+
+/// Archiving hides an aim from everyone, so it is up to whoever may delete it.
+pub fn validate_archiving(aim_address: &Address, sources: &[Address]) -> Result<(), String> {
+    if may_delete_aim(&aim_author(aim_address)?, sources) {
+        Ok(())
+    } else {
+        Err("only the author may archive this aim".into())
+    }
+}
+
+/// The links that lead to an aim can be removed by its author, and by whoever
+/// deleted the aim once it is gone.
+pub fn validate_aim_link_removal(aim_address: &Address, sources: &[Address]) -> Result<(), String> {
+    let author = aim_author(aim_address)?;
+    if sources.contains(&author) {
+        return Ok(());
+    }
+    let deleted = hdk::get_entry(aim_address)
+        .map_err(|_| String::from("could not load the aim"))?
+        .is_none();
+    if deleted && may_delete_aim(&author, sources) {
+        Ok(())
+    } else {
+        Err("only the author may remove the links of this aim while it exists".into())
+    }
+}
+
+/// Checks the removal of one of the links of a connection against the configured
+/// deletion policy.
+pub fn validate_connection_link_removal(connection_address: &Address, sources: &[Address]) -> Result<(), String> {
+    match deletion_policy() {
+        DeletionPolicy::Anyone => Ok(()),
+        DeletionPolicy::Author => {
+            let author = get_entry_author(connection_address)
+                .map_err(|_| String::from("could not determine the author of the connection"))?;
+            if sources.contains(&author) {
+                Ok(())
+            } else {
+                Err("only the author may remove the links of this connection".into())
+            }
+        }
+    }
+}
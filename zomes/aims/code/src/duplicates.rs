@@ -0,0 +1,162 @@
+use entries::load_linked_entries;
+
+use hdk::{
+    prelude::LinkMatch,
+};
+
+use hdk::holochain_persistence_api::{
+    cas::content::Address,
+};
+
+use hdk::holochain_json_api::{
+    error::JsonError,
+    json::JsonString,
+};
+
+use crate::{
+    Connection,
+    deletion::handle_delete_connection,
+    errors::{
+        AimsError,
+        AimsResult,
+    },
+    handle_get_root_aim_address,
+    weights::{
+        handle_update_connection_contribution,
+        relink_connection,
+    },
+};
+
+/// What `create_connection` does when the two aims are already connected.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MergePolicy {
+    /// Leave the existing connection as it is.
+    KeepExisting,
+    /// Give the existing connection the new contribution.
+    Replace,
+    /// Add the new contribution to the existing one.
+    Sum,
+    /// Fail instead of touching the existing connection.
+    Reject,
+}
+
+impl Default for MergePolicy {
+    fn default() -> Self {
+        MergePolicy::KeepExisting
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct MergedConnections {
+    contributing: Address,
+    receiving: Address,
+    kept: Address,
+    removed: Vec<Address>,
+    contribution: u32,
+}
+
+/// All distinct connections from `contributing` to `receiving`, in link order.
+pub fn connections_between(contributing: &Address, receiving: &Address) -> AimsResult<Vec<(Address, Connection)>> {
+    let mut connections: Vec<(Address, Connection)> = Vec::new();
+    for linked in load_linked_entries::<Connection>(receiving, "receives_from_connection")?.entries {
+        if connections.iter().any(|(address, _)| *address == linked.address) {
+            continue;
+        }
+        if linked.entry.contributing == *contributing {
+            connections.push((linked.address, linked.entry));
+        }
+    }
+    Ok(connections)
+}
+
+/// Applies the merge policy to an existing connection between the same two aims
+/// and returns the address of the connection that now stands for both.
+pub fn merge_into_existing(
+    existing_address: Address,
+    existing: &Connection,
+    contribution: u32,
+    merge_policy: MergePolicy,
+) -> AimsResult<Address> {
+    match merge_policy {
+        MergePolicy::KeepExisting => Ok(existing_address),
+        MergePolicy::Replace => handle_update_connection_contribution(existing_address, contribution),
+        MergePolicy::Sum => handle_update_connection_contribution(
+            existing_address,
+            existing.contribution.saturating_add(contribution)
+        ),
+        MergePolicy::Reject => Err(AimsError::conflict(format!(
+            "these aims are already connected by {}", existing_address
+        ))),
+    }
+}
+
+fn merge_inbound_duplicates(
+    receiving: &Address,
+    sum_contributions: bool,
+    merged: &mut Vec<MergedConnections>,
+) -> AimsResult<()> {
+    let mut contributors: Vec<Address> = Vec::new();
+    let linked = load_linked_entries::<Connection>(receiving, "receives_from_connection")?;
+    let link_count = linked.entries.len() + linked.failures.len();
+    for connection in linked.entries {
+        if !contributors.contains(&connection.entry.contributing) {
+            contributors.push(connection.entry.contributing);
+        }
+    }
+    if link_count == contributors.len() {
+        return Ok(());
+    }
+
+    for contributing in contributors {
+        let mut connections = connections_between(&contributing, receiving)?.into_iter();
+        let (kept, kept_connection) = match connections.next() {
+            Some(first) => first,
+            None => continue,
+        };
+        let removed: Vec<(Address, Connection)> = connections.collect();
+        // identical connection entries share an address, their links are collapsed
+        relink_connection(&kept, &kept, &kept_connection)?;
+        let mut contribution = kept_connection.contribution;
+        if removed.is_empty() {
+            continue;
+        }
+        if sum_contributions {
+            contribution = removed.iter()
+                .fold(contribution, |sum, (_, connection)| sum.saturating_add(connection.contribution));
+        }
+        for (address, _) in removed.iter() {
+            handle_delete_connection(address.clone())?;
+        }
+        merged.push(MergedConnections {
+            kept: handle_update_connection_contribution(kept, contribution)?,
+            removed: removed.into_iter().map(|(address, _)| address).collect(),
+            contributing,
+            receiving: receiving.clone(),
+            contribution,
+        });
+    }
+    Ok(())
+}
+
+/// Merges duplicate connections into the aims of this agent, keeping the first
+/// connection of each pair. With `sum_contributions` the kept connection gets the
+/// contributions of all duplicates, otherwise it keeps its own.
+pub fn handle_merge_duplicate_connections(sum_contributions: bool) -> AimsResult<Vec<MergedConnections>> {
+    let mut aim_addresses = hdk::get_links(
+        &hdk::AGENT_ADDRESS,
+        LinkMatch::Exactly("created_aim"),
+        LinkMatch::Any
+    )?.addresses();
+    if let Some(root_aim_address) = handle_get_root_aim_address()? {
+        if !aim_addresses.contains(&root_aim_address) {
+            aim_addresses.push(root_aim_address);
+        }
+    }
+
+    let mut merged = Vec::new();
+    for aim_address in aim_addresses {
+        merge_inbound_duplicates(&aim_address, sum_contributions, &mut merged)?;
+    }
+    Ok(merged)
+}
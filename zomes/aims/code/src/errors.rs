@@ -0,0 +1,125 @@
+use std::fmt;
+
+use entries::{
+    LoadError,
+    LoadFailure,
+};
+use hdk::error::ZomeApiError;
+
+use hdk::holochain_persistence_api::{
+    cas::content::Address,
+};
+
+use hdk::holochain_json_api::{
+    error::JsonError,
+    json::JsonString,
+};
+
+use std::convert::TryFrom;
+
+/// Everything that can go wrong in the aims zome. Errors serialize with a stable
+/// `code`, like `{"code": "not_found", "address": "Qm..."}`, so the UI can react
+/// to them without parsing messages.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+#[serde(tag = "code", rename_all = "snake_case")]
+pub enum AimsError {
+    /// There is no entry at the address, or it was deleted.
+    NotFound { address: Address },
+    /// The entry exists but is not of the expected type.
+    WrongEntryType { address: Address, expected: String },
+    /// The entry could not be read as the expected type.
+    Deserialize { address: Address, entry_type: String },
+    /// A link points to an entry that is missing the links it should have.
+    BrokenLink { address: Address, link_type: String },
+    /// A commit or link was rejected by validation.
+    ValidationFailed { reason: String },
+    /// The agent is not allowed to do this.
+    Unauthorized { reason: String },
+    /// The arguments of a zome call do not make sense.
+    InvalidInput { reason: String },
+    /// The change conflicts with the current state of the DHT.
+    Conflict { reason: String },
+    /// A call into another zome failed.
+    Remote { zome: String, reason: String },
+    /// Any other error reported by holochain.
+    Internal { reason: String },
+}
+
+pub type AimsResult<T> = Result<T, AimsError>;
+
+impl AimsError {
+    pub fn unauthorized(reason: &str) -> AimsError {
+        AimsError::Unauthorized { reason: reason.into() }
+    }
+
+    pub fn invalid_input<S: Into<String>>(reason: S) -> AimsError {
+        AimsError::InvalidInput { reason: reason.into() }
+    }
+
+    pub fn conflict<S: Into<String>>(reason: S) -> AimsError {
+        AimsError::Conflict { reason: reason.into() }
+    }
+}
+
+impl fmt::Display for AimsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", String::from(JsonString::from(self.clone())))
+    }
+}
+
+/// Errors pass through hdk functions as `ZomeApiError::Internal` holding their JSON,
+/// so they can be recovered on the way back.
+impl From<AimsError> for ZomeApiError {
+    fn from(error: AimsError) -> ZomeApiError {
+        ZomeApiError::Internal(error.to_string())
+    }
+}
+
+impl From<ZomeApiError> for AimsError {
+    fn from(error: ZomeApiError) -> AimsError {
+        match error {
+            ZomeApiError::Internal(reason) => {
+                match AimsError::try_from(JsonString::from_json(&reason)) {
+                    Ok(error) => error,
+                    Err(_) if reason.contains("ValidationFailed") => AimsError::ValidationFailed { reason },
+                    Err(_) => AimsError::Internal { reason },
+                }
+            },
+            ZomeApiError::ValidationFailed(reason) => AimsError::ValidationFailed { reason },
+            other => AimsError::Internal { reason: other.to_string() },
+        }
+    }
+}
+
+/// An error that only concerns one of the addresses a traversal came across, so
+/// the traversal could go on without it.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct AddressError {
+    pub address: Address,
+    pub error: AimsError,
+}
+
+impl From<LoadError> for AimsError {
+    fn from(error: LoadError) -> AimsError {
+        match error {
+            LoadError::NotFound { address } => AimsError::NotFound { address },
+            LoadError::WrongEntryType { address, expected } => AimsError::WrongEntryType { address, expected },
+            LoadError::Deserialize { address, entry_type } => AimsError::Deserialize { address, entry_type },
+            LoadError::Internal { reason } => AimsError::Internal { reason },
+        }
+    }
+}
+
+impl From<LoadFailure> for AddressError {
+    fn from(failure: LoadFailure) -> AddressError {
+        AddressError {
+            address: failure.address,
+            error: failure.error.into(),
+        }
+    }
+}
+
+/// Loads an app entry and checks it has the expected entry type.
+pub fn load_app_entry<T: TryFrom<JsonString>>(address: &Address, entry_type: &str) -> AimsResult<T> {
+    Ok(entries::load_app_entry(address, entry_type)?)
+}
@@ -0,0 +1,177 @@
+
+use hdk::holochain_persistence_api::{
+    cas::content::Address,
+};
+
+use hdk::holochain_json_api::{
+    error::JsonError,
+    json::JsonString,
+};
+
+use serde_json::{json, Map, Value};
+
+use crate::{
+    errors::{
+        AddressError,
+        AimsError,
+        AimsResult,
+    },
+    handle_get_root_aim_address,
+    tree::{
+        handle_get_aim_tree,
+        Direction,
+    },
+};
+
+/// Depth used when no limit is given, deep enough for any hand made aim network.
+pub const DEFAULT_EXPORT_DEPTH: u32 = 64;
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Dot,
+    Mermaid,
+    JsonGraph,
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct GraphExport {
+    format: ExportFormat,
+    content: String,
+    /// Aims and connections that could not be loaded and are missing from `content`.
+    errors: Vec<AddressError>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExportNode {
+    pub address: Address,
+    pub title: String,
+    pub color: String,
+}
+
+/// A contribution of `contributing` to `receiving`.
+#[derive(Debug, Clone)]
+pub struct ExportEdge {
+    pub contributing: Address,
+    pub receiving: Address,
+    pub contribution: u32,
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', " ")
+}
+
+pub fn render_dot(nodes: &[ExportNode], edges: &[ExportEdge]) -> String {
+    let mut dot = String::from("digraph aims {\n    node [style=filled];\n");
+    for node in nodes {
+        dot += &format!(
+            "    \"{}\" [label=\"{}\", fillcolor=\"{}\"];\n",
+            node.address, escape(&node.title), node.color
+        );
+    }
+    for edge in edges {
+        dot += &format!(
+            "    \"{}\" -> \"{}\" [label=\"{}\"];\n",
+            edge.contributing, edge.receiving, edge.contribution
+        );
+    }
+    dot + "}\n"
+}
+
+pub fn render_mermaid(nodes: &[ExportNode], edges: &[ExportEdge]) -> String {
+    // mermaid ids must be plain identifiers, so nodes are numbered
+    let id = |address: &Address| nodes.iter()
+        .position(|node| node.address == *address)
+        .map(|index| format!("aim{}", index))
+        .unwrap_or_default();
+    let mut mermaid = String::from("flowchart BT\n");
+    for (index, node) in nodes.iter().enumerate() {
+        mermaid += &format!("    aim{}[\"{}\"]\n", index, escape(&node.title).replace("\\\"", "#quot;"));
+    }
+    for edge in edges {
+        mermaid += &format!(
+            "    {} -->|{}| {}\n",
+            id(&edge.contributing), edge.contribution, id(&edge.receiving)
+        );
+    }
+    for (index, node) in nodes.iter().enumerate() {
+        mermaid += &format!("    style aim{} fill:{}\n", index, node.color);
+    }
+    mermaid
+}
+
+/// Renders a document following the JSON Graph Format (https://jsongraphformat.info).
+pub fn render_json_graph(nodes: &[ExportNode], edges: &[ExportEdge]) -> String {
+    let mut json_nodes = Map::new();
+    for node in nodes {
+        json_nodes.insert(String::from(node.address.clone()), json!({
+            "label": node.title,
+            "metadata": { "color": node.color }
+        }));
+    }
+    let json_edges: Vec<Value> = edges.iter()
+        .map(|edge| json!({
+            "source": String::from(edge.contributing.clone()),
+            "target": String::from(edge.receiving.clone()),
+            "relation": "contributes_to",
+            "metadata": { "contribution": edge.contribution }
+        }))
+        .collect();
+    json!({
+        "graph": {
+            "directed": true,
+            "type": "aims",
+            "nodes": json_nodes,
+            "edges": json_edges
+        }
+    }).to_string()
+}
+
+/// Exports all aims contributing to the given aim, or to the own root aim if none
+/// is given, together with their connections.
+pub fn handle_export_graph(
+    aim_address: Option<Address>,
+    format: ExportFormat,
+    max_depth: Option<u32>,
+) -> AimsResult<GraphExport> {
+    let start = match aim_address {
+        Some(address) => address,
+        None => handle_get_root_aim_address()?
+            .ok_or_else(|| AimsError::invalid_input("this agent has no root aim, give an aim to export"))?,
+    };
+    let tree = handle_get_aim_tree(start, Direction::Contributing, max_depth.unwrap_or(DEFAULT_EXPORT_DEPTH))?;
+
+    let mut nodes = vec![ExportNode {
+        address: tree.root_address.clone(),
+        title: tree.root_aim.title.clone(),
+        color: tree.root_aim.color_hex(),
+    }];
+    let mut edges = Vec::new();
+    for node in tree.nodes.iter() {
+        for connected in node.connected_aims.iter() {
+            if !nodes.iter().any(|existing| existing.address == connected.aim_address) {
+                nodes.push(ExportNode {
+                    address: connected.aim_address.clone(),
+                    title: connected.aim.title.clone(),
+                    color: connected.aim.color_hex(),
+                });
+            }
+            edges.push(ExportEdge {
+                contributing: connected.aim_address.clone(),
+                receiving: node.aim_address.clone(),
+                contribution: connected.connection.contribution,
+            });
+        }
+    }
+
+    let content = match format {
+        ExportFormat::Dot => render_dot(&nodes, &edges),
+        ExportFormat::Mermaid => render_mermaid(&nodes, &edges),
+        ExportFormat::JsonGraph => render_json_graph(&nodes, &edges),
+    };
+    Ok(GraphExport {
+        format,
+        content,
+        errors: tree.errors,
+    })
+}
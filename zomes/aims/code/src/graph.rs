@@ -0,0 +1,97 @@
+use aim_graph::{
+    AimStore,
+    Connected,
+    Direction,
+};
+
+use std::cell::RefCell;
+
+use hdk::holochain_persistence_api::{
+    cas::content::Address,
+};
+
+use crate::{
+    Aim,
+    Connection,
+    errors::{
+        AddressError,
+        AimsError,
+    },
+    get_aim,
+    load_connected_aims,
+};
+
+/// The link relation that leads from an aim in the given direction.
+pub fn relation(direction: &Direction) -> &'static str {
+    match direction {
+        Direction::Contributing => "receives_from",
+        Direction::Receiving => "contributes_to",
+    }
+}
+
+impl Aim {
+    /// This aim as the graph algorithms see it.
+    pub fn to_graph(&self) -> aim_graph::Aim {
+        aim_graph::Aim {
+            title: self.title.clone(),
+            description: self.description.clone(),
+            effort: self.effort.clone(),
+            color: self.color,
+            tags: self.tags.clone(),
+            status: self.status.clone(),
+            progress: self.progress,
+        }
+    }
+}
+
+impl Connection {
+    pub fn to_graph(&self) -> aim_graph::Connection<Address> {
+        aim_graph::Connection {
+            contributing: self.contributing.clone(),
+            receiving: self.receiving.clone(),
+            contribution: self.contribution,
+        }
+    }
+}
+
+/// Reads the contribution graph from the DHT. Like `load_connected_aims`, it leaves
+/// out archived aims and goes on past connections that cannot be followed; those
+/// are collected so the results can report them.
+#[derive(Default)]
+pub struct HdkStore {
+    errors: RefCell<Vec<AddressError>>,
+}
+
+impl HdkStore {
+    pub fn new() -> HdkStore {
+        HdkStore::default()
+    }
+
+    /// The connections that could not be followed so far.
+    pub fn into_errors(self) -> Vec<AddressError> {
+        self.errors.into_inner()
+    }
+}
+
+impl AimStore for HdkStore {
+    type Id = Address;
+    type Error = AimsError;
+
+    fn aim(&self, id: &Address) -> Result<aim_graph::Aim, AimsError> {
+        Ok(get_aim(id)?.to_graph())
+    }
+
+    fn connected(&self, id: &Address, direction: Direction) -> Result<Vec<Connected<Address>>, AimsError> {
+        let connected_aims = load_connected_aims(id, relation(&direction))?;
+        self.errors.borrow_mut().extend(connected_aims.errors);
+        Ok(connected_aims.aims
+            .into_iter()
+            .map(|connected| Connected {
+                aim: connected.aim.to_graph(),
+                aim_id: connected.aim_address,
+                connection_id: connected.connection_address,
+                contribution: connected.connection.contribution,
+            })
+            .collect())
+    }
+}
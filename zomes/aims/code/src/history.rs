@@ -0,0 +1,156 @@
+use std::collections::BTreeSet;
+
+use hdk::holochain_core_types::entry::Entry;
+use hdk::holochain_wasm_utils::api_serialization::get_entry::{
+    GetEntryOptions,
+    GetEntryResultItem,
+    GetEntryResultType,
+    StatusRequestKind,
+};
+
+use hdk::holochain_persistence_api::{
+    cas::content::Address,
+};
+
+use hdk::holochain_json_api::{
+    error::JsonError,
+    json::JsonString,
+};
+
+use serde_json::Value;
+
+use std::convert::TryFrom;
+
+use crate::{
+    Aim,
+    errors::{
+        AimsError,
+        AimsResult,
+    },
+    handle_update_aim,
+    initial_header,
+    roles::original_entry_address,
+};
+
+/// One version of an aim, as it was committed.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct AimVersion {
+    address: Address,
+    aim: Aim,
+    author: Address,
+    committed_at: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct FieldChange {
+    field: String,
+    from: Value,
+    to: Value,
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct AimDiff {
+    from_version: Address,
+    to_version: Address,
+    changes: Vec<FieldChange>,
+    added_tags: Vec<String>,
+    removed_tags: Vec<String>,
+}
+
+fn version_from_item(item: GetEntryResultItem) -> Option<AimVersion> {
+    let header = item.headers.first()?;
+    let aim = match item.entry? {
+        Entry::App(_, json_string) => Aim::try_from(json_string).ok()?,
+        _ => return None,
+    };
+    Some(AimVersion {
+        address: header.entry_address().clone(),
+        author: header.provenances().first()?.source(),
+        committed_at: header.timestamp().to_string(),
+        aim,
+    })
+}
+
+/// Loads exactly the version at the given address, not the latest one like `get_aim`.
+fn load_version(version_address: &Address) -> AimsResult<AimVersion> {
+    let result = hdk::get_entry_result(
+        version_address,
+        GetEntryOptions::new(StatusRequestKind::Initial, true, true, Default::default())
+    )?;
+    match result.result {
+        GetEntryResultType::Single(item) => version_from_item(item)
+            .ok_or_else(|| AimsError::WrongEntryType {
+                address: version_address.clone(),
+                expected: "aim".into(),
+            }),
+        _ => Err(AimsError::NotFound { address: version_address.clone() }),
+    }
+}
+
+/// Every version of an aim, oldest first. Any version's address can be given.
+pub fn handle_get_aim_history(aim_address: Address) -> AimsResult<Vec<AimVersion>> {
+    let original_address = original_entry_address(&initial_header(&aim_address)?)?;
+    let result = hdk::get_entry_result(
+        &original_address,
+        GetEntryOptions::new(StatusRequestKind::All, true, true, Default::default())
+    )?;
+    let items = match result.result {
+        GetEntryResultType::All(history) => history.items,
+        GetEntryResultType::Single(item) => vec![item],
+    };
+    Ok(items.into_iter().filter_map(version_from_item).collect())
+}
+
+fn field_change<T: PartialEq + Into<Value>>(changes: &mut Vec<FieldChange>, field: &str, from: T, to: T) {
+    if from != to {
+        changes.push(FieldChange {
+            field: field.into(),
+            from: from.into(),
+            to: to.into(),
+        });
+    }
+}
+
+/// Lists the fields that differ between two versions of an aim.
+pub fn handle_diff_aim_versions(from_version: Address, to_version: Address) -> AimsResult<AimDiff> {
+    let from = load_version(&from_version)?.aim;
+    let to = load_version(&to_version)?.aim;
+
+    let mut changes = Vec::new();
+    field_change(&mut changes, "title", from.title.clone(), to.title.clone());
+    field_change(&mut changes, "description", from.description.clone(), to.description.clone());
+    field_change(&mut changes, "effort", from.effort.to_string(), to.effort.to_string());
+    field_change(&mut changes, "color", from.color.iter().collect::<String>(), to.color.iter().collect::<String>());
+    field_change(&mut changes, "tags", from.tags.clone(), to.tags.clone());
+
+    let from_tags: BTreeSet<String> = from.tags.into_iter().collect();
+    let to_tags: BTreeSet<String> = to.tags.into_iter().collect();
+    Ok(AimDiff {
+        from_version,
+        to_version,
+        changes,
+        added_tags: to_tags.difference(&from_tags).cloned().collect(),
+        removed_tags: from_tags.difference(&to_tags).cloned().collect(),
+    })
+}
+
+/// Restores title, description, effort, color and tags of an earlier version as a
+/// new update, so the reverted versions stay in the history. Status and progress
+/// are kept as they are.
+pub fn handle_revert_aim(aim_address: Address, version_address: Address) -> AimsResult<Address> {
+    let is_own_version = handle_get_aim_history(aim_address.clone())?.iter()
+        .any(|version| version.address == version_address);
+    if !is_own_version {
+        return Err(AimsError::invalid_input("this is not a version of the given aim"));
+    }
+    let version = load_version(&version_address)?.aim;
+    handle_update_aim(
+        aim_address,
+        version.title,
+        version.description,
+        version.effort,
+        version.timestamp_ms,
+        version.color,
+        version.tags,
+    )
+}
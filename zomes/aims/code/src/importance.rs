@@ -0,0 +1,102 @@
+use aim_graph::importance::{
+    compute_importance,
+    CONVERGENCE_EPSILON,
+    DEFAULT_DAMPING,
+    MAX_ITERATIONS,
+};
+use aim_graph::traversal::collect_edges;
+
+use hdk::{
+    prelude::LinkMatch,
+};
+
+use hdk::holochain_persistence_api::{
+    cas::content::Address,
+};
+
+use hdk::holochain_json_api::{
+    error::JsonError,
+    json::JsonString,
+};
+
+use crate::{
+    errors::{
+        AddressError,
+        AimsResult,
+    },
+    graph::HdkStore,
+    root_aims_anchor_address,
+    handle_get_root_aim_address,
+};
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct AimImportance {
+    aim_address: Address,
+    importance: f64,
+}
+
+/// Collects the root aims of all agents. Root aims are registered on a DNA wide
+/// anchor when they are created; the own root aim is always included.
+pub fn get_all_root_aim_addresses() -> AimsResult<Vec<Address>> {
+    let mut roots = hdk::get_links(
+        &root_aims_anchor_address()?,
+        LinkMatch::Exactly("root_aim"),
+        LinkMatch::Any
+    )?.addresses();
+    if let Some(own_root) = handle_get_root_aim_address()? {
+        if !roots.contains(&own_root) {
+            roots.push(own_root);
+        }
+    }
+    Ok(roots)
+}
+
+/// The importance of all aims reachable from the root aims, most important first.
+/// Connections that could not be followed are listed in `errors`; the aims behind
+/// them are missing from the map.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct ImportanceMap {
+    aims: Vec<AimImportance>,
+    errors: Vec<AddressError>,
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct AimImportanceResult {
+    aim_address: Address,
+    importance: f64,
+    errors: Vec<AddressError>,
+}
+
+pub fn handle_compute_importance_map() -> AimsResult<ImportanceMap> {
+    let roots = get_all_root_aim_addresses()?;
+    let store = HdkStore::new();
+    let edges = collect_edges(&store, &roots)?;
+    let mut aims: Vec<AimImportance> = compute_importance(
+        &roots,
+        &edges,
+        DEFAULT_DAMPING,
+        CONVERGENCE_EPSILON,
+        MAX_ITERATIONS
+    ).into_iter()
+        .map(|(aim_address, importance)| AimImportance { aim_address, importance })
+        .collect();
+    aims.sort_by(|a, b| b.importance.partial_cmp(&a.importance).unwrap_or(std::cmp::Ordering::Equal));
+    Ok(ImportanceMap {
+        aims,
+        errors: store.into_errors(),
+    })
+}
+
+pub fn handle_get_aim_importance(aim_address: Address) -> AimsResult<AimImportanceResult> {
+    let map = handle_compute_importance_map()?;
+    let importance = map.aims
+        .into_iter()
+        .find(|entry| entry.aim_address == aim_address)
+        .map(|entry| entry.importance)
+        .unwrap_or(0.0);
+    Ok(AimImportanceResult {
+        aim_address,
+        importance,
+        errors: map.errors,
+    })
+}
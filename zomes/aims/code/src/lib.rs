@@ -4,24 +4,29 @@ extern crate serde;
 #[macro_use]
 extern crate serde_derive;
 extern crate serde_json;
+extern crate chrono;
+extern crate entries;
+extern crate aim_graph;
 #[macro_use]
 extern crate holochain_json_derive;
 
 use hdk::{
     entry_definition::ValidatingEntryType,
-    error::{
-        ZomeApiResult,
-        ZomeApiError
-    },
 	prelude::{
         EntryType,
 		LinkMatch,
 	}
 };
 use hdk::holochain_core_types::{
+    chain_header::ChainHeader,
     entry::Entry,
     dna::entry_types::Sharing,
 };
+use hdk::holochain_wasm_utils::api_serialization::get_entry::{
+    GetEntryOptions,
+    GetEntryResultType,
+    StatusRequestKind,
+};
 
 use hdk::holochain_persistence_api::{
     cas::content::Address,
@@ -32,51 +37,186 @@ use hdk::holochain_json_api::{
     json::JsonString,
 };
 
-use std::convert::TryFrom;
+use std::collections::HashMap;
 
+pub mod importance;
+pub mod comments;
+pub mod consent;
+pub mod cycles;
+pub mod deletion;
+pub mod duplicates;
+pub mod errors;
+pub mod export;
+pub mod graph;
+pub mod history;
+pub mod outline;
+pub mod paths;
+pub mod profiles;
+pub mod properties;
+pub mod roles;
+pub mod root;
+pub mod rollup;
+pub mod signals;
+pub mod status;
+pub mod tags;
+pub mod tree;
+pub mod validation;
+pub mod weights;
 
-#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
-pub enum Effort {
-    Minutes(u64), 
-    Hours(u64), 
-    Days(u64), 
-    Weeks(u64), 
-    Months(u64), 
-    Years(u64),
-}
-
-impl ToString for Effort {
-    fn to_string(&self) -> String {
-        match self {
-            Effort::Minutes(m) => m.to_string() + "min",
-            Effort::Hours(h) => h.to_string() + "h", 
-            Effort::Days(d) => d.to_string() + "d", 
-            Effort::Weeks(w) => w.to_string() + "w", 
-            Effort::Months(m) => m.to_string() + "m",
-            Effort::Years(y) => y.to_string() + "y"
-        }
-    }
-}
+use comments::{
+    CommentList,
+    comment_entry_definition,
+    handle_add_comment,
+    handle_edit_comment,
+    handle_delete_comment,
+    handle_get_comments,
+};
+use consent::{
+    Consent,
+    ProposalList,
+    connection_proposal_entry_definition,
+    handle_propose_connection,
+    handle_get_connection_proposals,
+    handle_accept_connection_proposal,
+    handle_decline_connection_proposal,
+    validate_connection_consent,
+};
+use cycles::{
+    CycleWarning,
+    check_connection_cycle,
+    handle_find_cycles,
+    FoundCycles,
+};
+use deletion::{
+    handle_archive_aim,
+    handle_unarchive_aim,
+    handle_get_archived_aims,
+    handle_delete_connection,
+    handle_delete_aim,
+    is_archived,
+    validate_aim_link_removal,
+    validate_archiving,
+    validate_deletion,
+    validate_connection_link_removal,
+};
+use duplicates::{
+    MergePolicy,
+    MergedConnections,
+    connections_between,
+    handle_merge_duplicate_connections,
+    merge_into_existing,
+};
+pub use aim_graph::Effort;
+pub use entries::AddressAndEntry;
+use entries::load_linked_entries;
+use errors::{
+    AddressError,
+    AimsError,
+    AimsResult,
+    load_app_entry,
+};
+use export::{
+    ExportFormat,
+    GraphExport,
+    handle_export_graph,
+};
+use history::{
+    AimDiff,
+    AimVersion,
+    handle_get_aim_history,
+    handle_diff_aim_versions,
+    handle_revert_aim,
+};
+use importance::{
+    AimImportanceResult,
+    ImportanceMap,
+    handle_compute_importance_map,
+    handle_get_aim_importance,
+};
+use outline::{
+    ImportReport,
+    OutlineFormat,
+    handle_import_outline,
+};
+use paths::{
+    AimPaths,
+    handle_get_paths_to_root,
+};
+use roles::{
+    Member,
+    InvitationList,
+    RevokedRoles,
+    Role,
+    can_maintain,
+    get_members,
+    membership_entry_definition,
+    handle_assign_role,
+    handle_invite_member,
+    handle_get_invitations,
+    handle_accept_invitation,
+    handle_revoke_role,
+    original_entry_address,
+    validate_can_maintain,
+};
+use root::{
+    RootRepair,
+    handle_customize_root_aim,
+    handle_migrate_root_aim,
+    handle_replace_root_aim,
+    handle_repair_root_aims,
+    link_root_aim,
+    personal_root_aim,
+    validate_root_aim_link,
+    validate_root_aim_registration,
+    validate_root_aim_unregistration,
+    validate_root_of,
+};
+use rollup::{
+    AimCompletion,
+    SubtreeEffort,
+    handle_get_aim_completion,
+    handle_get_subtree_effort,
+};
+use signals::{
+    handle_receive,
+    notify_receiving_author,
+    signal_aim_created,
+    signal_aim_updated,
+    signal_connection_created,
+};
+use status::{
+    AimStatus,
+    StatusChange,
+    handle_set_aim_status,
+    validate_status_change,
+};
+use tags::{
+    TagCount,
+    TagMatch,
+    TagScope,
+    link_tags,
+    relink_tags,
+    validate_tag_link_removal,
+    handle_get_aims_by_tag,
+    handle_get_aims_by_tags,
+    handle_list_tags_with_counts,
+};
+use tree::{
+    AimTree,
+    Direction,
+    handle_get_aim_tree,
+};
+use validation::{
+    validate_aim_content,
+    validate_connection_content,
+};
+use weights::{
+    ContributionWeight,
+    handle_update_connection_contribution,
+    handle_set_contribution_weights,
+    validate_connection_update,
+};
 
-impl Effort {
-/*    fn from_string(mut s: String) -> Effort {
-        s.retain(|c| !c.is_whitespace()); 
-        let len = s.chars().count();
-        let cutoff = | n | {
-            let number_str: String = s.chars().take(len - n).collect();
-            number_str.parse::<u64>().unwrap()
-        };
-        match s.chars().last().unwrap() {
-            'n' => Effort::Minutes(cutoff(3)), 
-            'h' => Effort::Hours(cutoff(1)), 
-            'd' => Effort::Days(cutoff(1)), 
-            'w' => Effort::Weeks(cutoff(1)), 
-            'm' => Effort::Months(cutoff(1)), 
-            'y' => Effort::Years(cutoff(1)),
-            _ => Effort::Days(1u64) // default
-        }
-    }*/
-}
 
 #[derive(Serialize, Deserialize, Debug, DefaultJson,Clone)]
 pub struct Aim {
@@ -86,13 +226,36 @@ pub struct Aim {
 	timestamp_ms: i64,
     color: [char; 6],
     tags: Vec<String>,
+    #[serde(default)]
+    status: AimStatus,
+    #[serde(default)]
+    progress: u8,
+    #[serde(default)]
+    status_change: Option<StatusChange>,
+    /// The agent whose root aim this is. Root aims of different agents would
+    /// otherwise be identical entries with the same address.
+    #[serde(default)]
+    root_of: Option<Address>,
+}
+
+impl Aim {
+    /// The color as css hex notation, like "#55aa00".
+    pub fn color_hex(&self) -> String {
+        format!("#{}", self.color.iter().collect::<String>())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct Anchor {
+    name: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
 pub struct Connection {
     contributing: Address, 
     receiving: Address,
-    contribution: u32
+    contribution: u32,
+    consent: Option<Consent>,
 }
 
 pub fn handle_create_aim(
@@ -102,23 +265,29 @@ pub fn handle_create_aim(
 	timestamp_ms: i64,
     color: [char; 6], 
     tags: Vec<String>,
-) -> ZomeApiResult<Address> {
+) -> AimsResult<Address> {
 	let aim = Aim {
 		title,
 		description, 
         color, 
         effort, 
 		timestamp_ms, 
-        tags,
+        tags: tags.clone(),
+        status: AimStatus::Open,
+        progress: 0,
+        status_change: None,
+        root_of: None,
 	};
     let entry = Entry::App("aim".into(), aim.into());
     let address = hdk::commit_entry(&entry)?;
+    link_tags(&address, &tags)?;
 	hdk::link_entries(
 		&hdk::AGENT_ADDRESS.clone(), 
 		&address, 
 		"created_aim",
 		""
 	)?;
+    signal_aim_created(&address);
     Ok(address)
 }
 
@@ -130,17 +299,25 @@ pub fn handle_update_aim(
 	timestamp_ms: i64,
     color: [char; 6], 
     tags: Vec<String>, 
-) -> ZomeApiResult<Address>{
+) -> AimsResult<Address>{
+    let old_aim = get_aim(&aim_address)?;
 	let aim = Aim {
 		title,
 		description, 
         color, 
         effort, 
 		timestamp_ms, 
-        tags,
+        tags: tags.clone(),
+        status: old_aim.status,
+        progress: old_aim.progress,
+        status_change: old_aim.status_change,
+        root_of: old_aim.root_of,
 	};
     let entry = Entry::App("aim".into(), aim.into());
-    hdk::update_entry(entry, &aim_address)
+    let address = hdk::update_entry(entry, &aim_address)?;
+    relink_tags(&aim_address, &old_aim.tags, &tags)?;
+    signal_aim_updated(&aim_address, &address);
+    Ok(address)
 }
 
 pub fn handle_create_receiving_aim(
@@ -151,9 +328,9 @@ pub fn handle_create_receiving_aim(
     color: [char; 6], 
     tags: Vec<String>, 
     contributing_aim_address: Address, 
-) -> ZomeApiResult<Address> {
+) -> AimsResult<Address> {
     let new_aim_address = handle_create_aim(title, description, effort, timestamp_ms, color, tags)?;
-    handle_create_connection(contributing_aim_address, new_aim_address.clone(), 1)?;
+    handle_create_connection(contributing_aim_address, new_aim_address.clone(), 1, None)?;
     Ok(new_aim_address)
 }
 
@@ -165,23 +342,70 @@ pub fn handle_create_contributing_aim(
     color: [char; 6], 
     tags: Vec<String>, 
     receiving_aim_address: Address
-) -> ZomeApiResult<Address> {
+) -> AimsResult<Address> {
+    let receiving_is_own = can_maintain(&receiving_aim_address, &hdk::AGENT_ADDRESS)?;
     let new_aim_address = handle_create_aim(title, description, effort, timestamp_ms, color, tags)?; 
-    handle_create_connection(new_aim_address.clone(), receiving_aim_address, 1)?; 
+    if receiving_is_own {
+        handle_create_connection(new_aim_address.clone(), receiving_aim_address, 1, None)?; 
+    } else {
+        handle_propose_connection(new_aim_address.clone(), receiving_aim_address, 1, timestamp_ms)?;
+    }
     Ok(new_aim_address)
 }
 
+/// Connects two aims directly. This is only possible for owners and maintainers of
+/// the receiving aim, everybody else has to propose the connection. If the aims are
+/// already connected, no second connection is committed: the existing one is
+/// returned and treated according to `merge_policy`, which defaults to keeping it.
 pub fn handle_create_connection(
     contributing_aim_address: Address, 
     receiving_aim_address: Address, 
     contribution: u32,
-) -> ZomeApiResult<()> {
+    merge_policy: Option<MergePolicy>,
+) -> AimsResult<CreatedConnection> {
+    if !can_maintain(&receiving_aim_address, &hdk::AGENT_ADDRESS)? {
+        return Err(AimsError::unauthorized("you do not maintain the receiving aim, propose the connection instead"));
+    }
     let connection = Connection {
-        contributing: contributing_aim_address.clone(), 
-        receiving: receiving_aim_address.clone(), 
-        contribution
+        contributing: contributing_aim_address, 
+        receiving: receiving_aim_address, 
+        contribution,
+        consent: None,
     }; 
-    let entry = Entry::App("connection".into(), connection.into()); 
+    let created = commit_connection(connection.clone(), merge_policy.unwrap_or_default())?;
+    if !created.merged {
+        notify_receiving_author(&created.connection_address, &connection)?;
+    }
+    Ok(created)
+}
+
+/// The connection that now connects two aims.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct CreatedConnection {
+    connection_address: Address,
+    /// Whether the aims were connected already and the existing connection was
+    /// merged instead of committing a new one.
+    merged: bool,
+    /// Cycles the cycle policy let through.
+    warnings: Vec<CycleWarning>,
+}
+
+/// Commits and links a connection. If the two aims are already connected, the
+/// existing connection is merged according to `merge_policy` instead.
+pub fn commit_connection(connection: Connection, merge_policy: MergePolicy) -> AimsResult<CreatedConnection> {
+    let contributing_aim_address = connection.contributing.clone();
+    let receiving_aim_address = connection.receiving.clone();
+    if let Some((existing_address, existing)) = connections_between(&contributing_aim_address, &receiving_aim_address)?.into_iter().next() {
+        return Ok(CreatedConnection {
+            connection_address: merge_into_existing(existing_address, &existing, connection.contribution, merge_policy)?,
+            merged: true,
+            warnings: Vec::new(),
+        });
+    }
+    let warnings: Vec<CycleWarning> = check_connection_cycle(&contributing_aim_address, &receiving_aim_address)?
+        .into_iter()
+        .collect();
+    let entry = Entry::App("connection".into(), connection.clone().into()); 
     let connection_address = hdk::commit_entry(&entry)?;
 
     hdk::link_entries(&contributing_aim_address, &connection_address, "contributes_to_connection", "")?;
@@ -189,39 +413,137 @@ pub fn handle_create_connection(
     hdk::link_entries(&receiving_aim_address, &connection_address, "receives_from_connection", "")?;
     hdk::link_entries(&connection_address, &contributing_aim_address, "receives_from_aim", "")?;
 
-    Ok(())
+    signal_connection_created(&connection_address, &connection);
+    Ok(CreatedConnection {
+        connection_address,
+        merged: false,
+        warnings,
+    })
+}
+
+/// The header that committed exactly the version at the given address.
+pub fn initial_header(address: &Address) -> AimsResult<ChainHeader> {
+    let result = hdk::get_entry_result(
+        address,
+        GetEntryOptions::new(StatusRequestKind::Initial, false, true, Default::default())
+    )?;
+    match result.result {
+        GetEntryResultType::Single(item) => item.headers.first().cloned()
+            .ok_or_else(|| AimsError::NotFound { address: address.clone() }),
+        _ => Err(AimsError::NotFound { address: address.clone() }),
+    }
 }
 
-pub fn handle_get_aims() -> ZomeApiResult<Vec<Aim>> {
-	hdk::utils::get_links_and_load_type(
-		&hdk::AGENT_ADDRESS, 
+/// Looks up the agent who committed the first version of the entry at the given
+/// address. Any version's address can be given; whoever updated the entry later
+/// does not become its author.
+pub fn get_entry_author(address: &Address) -> AimsResult<Address> {
+    let header = initial_header(address)?;
+    let original_address = original_entry_address(&header)?;
+    let original_header = if original_address == *address {
+        header
+    } else {
+        initial_header(&original_address)?
+    };
+    original_header.provenances().first()
+        .map(|provenance| provenance.source())
+        .ok_or_else(|| AimsError::NotFound { address: address.clone() })
+}
+
+/// A page of aims, with the aims that could not be loaded listed in `errors`.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct AimList {
+    aims: Vec<AddressAndEntry<Aim>>,
+    errors: Vec<AddressError>,
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AimOrder {
+    TimestampMs,
+    Title,
+    Effort,
+}
+
+pub fn handle_get_aims(
+    order: Option<AimOrder>,
+    descending: Option<bool>,
+    offset: Option<u32>,
+    limit: Option<u32>,
+) -> AimsResult<AimList> {
+    handle_get_aims_of_agent(hdk::AGENT_ADDRESS.clone(), order, descending, offset, limit)
+}
+
+/// Lists the aims an agent created, except for archived ones, optionally sorted
+/// and paginated.
+pub fn handle_get_aims_of_agent(
+    agent_address: Address,
+    order: Option<AimOrder>,
+    descending: Option<bool>,
+    offset: Option<u32>,
+    limit: Option<u32>,
+) -> AimsResult<AimList> {
+    let mut aims = Vec::new();
+    let mut errors = Vec::new();
+    for aim_address in hdk::get_links(
+		&agent_address, 
 		LinkMatch::Exactly("created_aim"),
 		LinkMatch::Any
-	)
+	)?.addresses() {
+        if is_archived(&aim_address)? {
+            continue;
+        }
+        match load_app_entry(&aim_address, "aim") {
+            Ok(aim) => aims.push(AddressAndEntry {
+                entry: aim,
+                address: aim_address,
+            }),
+            Err(error) => errors.push(AddressError {
+                address: aim_address,
+                error,
+            }),
+        }
+    }
+
+    if let Some(order) = order {
+        aims.sort_by(|a, b| match order {
+            AimOrder::TimestampMs => a.entry.timestamp_ms.cmp(&b.entry.timestamp_ms),
+            AimOrder::Title => a.entry.title.to_lowercase().cmp(&b.entry.title.to_lowercase()),
+            AimOrder::Effort => a.entry.effort.cmp(&b.entry.effort),
+        });
+        if descending.unwrap_or(false) {
+            aims.reverse();
+        }
+    }
+
+    Ok(AimList {
+        aims: aims.into_iter()
+            .skip(offset.unwrap_or(0) as usize)
+            .take(limit.map_or(usize::MAX, |limit| limit as usize))
+            .collect(),
+        errors,
+    })
 }
 
 #[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
 pub struct AimDetails {
     aim: Aim,
-    // more stuff like roles in the future i hope
-}
-
-pub fn handle_get_aim_details(aim_address: Address) -> ZomeApiResult<AimDetails> {
-    match hdk::get_entry(&aim_address) {
-        Ok(option) => match option {
-            Some(entry) => match entry {
-                Entry::App(_, json_string) => match Aim::try_from(json_string.to_owned()) {
-                    Ok(aim) => Ok(AimDetails {
-                        aim
-                    }),
-                    Err(_) => Err(ZomeApiError::Internal("could not parse entry json string".into()))
-                }
-                _ => Err(ZomeApiError::Internal("not an app entry".into()))
-            }, 
-            None => Err(ZomeApiError::Internal("could not find this entry".into()))
-        }, 
-        Err(err) => Err(err)
-    }
+    members: Vec<Member>,
+    /// Memberships that could not be loaded.
+    errors: Vec<AddressError>,
+}
+
+pub fn get_aim(aim_address: &Address) -> AimsResult<Aim> {
+    load_app_entry(aim_address, "aim")
+}
+
+pub fn handle_get_aim_details(aim_address: Address) -> AimsResult<AimDetails> {
+    let mut errors = Vec::new();
+    Ok(AimDetails {
+        aim: get_aim(&aim_address)?,
+        members: get_members(&aim_address, &mut errors)?,
+        errors,
+    })
 }
 
 #[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
@@ -232,124 +554,130 @@ pub struct ConnectedAim{
     connection_address: Address, 
 }
 
-pub fn handle_get_agent_address() -> ZomeApiResult<Address> {
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct ConnectedAims {
+    aims: Vec<ConnectedAim>,
+    errors: Vec<AddressError>,
+}
+
+pub fn handle_get_agent_address() -> AimsResult<Address> {
 	Ok(hdk::AGENT_ADDRESS.clone())
 }
 
-pub fn handle_create_root_aim() -> ZomeApiResult<Address> {
-    let aim = Aim {
-        title: String::from("root aim"), 
-        description: String::from("this is the single root aim of this agent. Some algorithms will use these root aims as the main source of collective will: using these aims as the only entrance of importance flow before calculating the absolute importance for each goal"),
-        effort: Effort::Years(100), 
-        timestamp_ms: 1594443995818, 
-        color: ['5'; 6],
-        tags: Vec::<String>::new(),
+pub const ROOT_AIMS_ANCHOR: &str = "root_aims";
+
+fn anchor_entry(name: &str) -> Entry {
+    let anchor = Anchor {
+        name: name.into(),
     };
-    let entry = Entry::App("aim".into(), aim.into());
+    Entry::App("anchor".into(), anchor.into())
+}
+
+/// The address of an anchor, without committing it. Queries use it as is; before
+/// linking from or to an anchor, `commit_anchor` makes sure it exists.
+pub fn anchor_address(name: &str) -> AimsResult<Address> {
+    Ok(hdk::entry_address(&anchor_entry(name))?)
+}
+
+pub fn commit_anchor(name: &str) -> AimsResult<Address> {
+    Ok(hdk::commit_entry(&anchor_entry(name))?)
+}
+
+pub fn root_aims_anchor_address() -> AimsResult<Address> {
+    anchor_address(ROOT_AIMS_ANCHOR)
+}
+
+/// Creates the root aim of this agent, or returns it if there already is one.
+pub fn handle_create_root_aim() -> AimsResult<Address> {
+    if let Some(address) = handle_get_root_aim_address()? {
+        return Ok(address);
+    }
+    let entry = Entry::App("aim".into(), personal_root_aim()?.into());
     let address = hdk::commit_entry(&entry)?;
-    hdk::link_entries(
-        &hdk::AGENT_ADDRESS.clone(), 
-        &address, 
-        "has_root_aim", 
-        ""
-    )
+    link_root_aim(&address)?;
+    Ok(address)
 }
 
-pub fn handle_get_root_aim_address_or_create() -> ZomeApiResult<Address> {
-    match handle_get_root_aim_address(){
-        Ok(option) => match option {
-            Some(address) => Ok(address), 
-            None => match handle_create_root_aim() {
-                Ok(address) => Ok(address), 
-                Err(err) => Err(err)
-            }
-        },
-        Err(err) => Err(err) 
+pub fn handle_get_root_aim_address_or_create() -> AimsResult<Address> {
+    match handle_get_root_aim_address()? {
+        Some(address) => Ok(address), 
+        None => handle_create_root_aim(),
     }
 }
 
-pub fn handle_get_root_aim_address() -> ZomeApiResult<Option<Address>>{
-    match hdk::get_links(
+pub fn handle_get_root_aim_address() -> AimsResult<Option<Address>>{
+    Ok(hdk::get_links(
         &hdk::AGENT_ADDRESS.clone(), 
         LinkMatch::Exactly("has_root_aim"), 
         LinkMatch::Any
-    ) {
-        Ok(result) => Ok(match result.addresses().first() {
-            Some(address) => Some(address.clone()), 
-            None => None
-        }),
-        Err(err) => Err(err)
-    }
+    )?.addresses().first().cloned())
 }
 
 pub fn handle_get_receiving_aims(
     contributing_aim_address: Address, 
-) -> ZomeApiResult<Vec<ConnectedAim>> {
-    get_connected_aims(contributing_aim_address, "contributes_to".into())
+) -> AimsResult<ConnectedAims> {
+    load_connected_aims(&contributing_aim_address, "contributes_to")
 }
 
 pub fn handle_get_contributing_aims(
     receiving_aim_address: Address, 
-) -> ZomeApiResult<Vec<ConnectedAim>> {
-    get_connected_aims(receiving_aim_address, "receives_from".into())
+) -> AimsResult<ConnectedAims> {
+    load_connected_aims(&receiving_aim_address, "receives_from")
 }
 
-pub fn get_connected_aims (
-    receiving_aim_address: Address, 
-    relation: String
-) -> ZomeApiResult<Vec<ConnectedAim>> {
-    Ok( hdk::get_links(
-        &receiving_aim_address, 
-        LinkMatch::Exactly(&format!("{}_connection", relation)), 
+fn load_connected_aim(connection: AddressAndEntry<Connection>, relation: &str) -> AimsResult<ConnectedAim> {
+    let link_type = format!("{}_aim", relation);
+    let aim_address = hdk::get_links(
+        &connection.address,
+        LinkMatch::Exactly(&link_type),
         LinkMatch::Any
-    )?.addresses().iter()
-        .filter_map(|connection_address| match hdk::api::get_entry(connection_address) {
-            Ok(connection_option) => match connection_option {
-                Some(connection_entry) => match match hdk::get_links(
-                        connection_address,
-                        LinkMatch::Exactly(&format!("{}_aim", relation)),
-                        LinkMatch::Any
-                    ) {
-                    Ok(result) => result.addresses().iter()
-                        .map(|aim_address| match hdk::api::get_entry(aim_address) {
-                            Ok(aim_option) => match aim_option {
-                                Some(aim_entry) => match connection_entry.clone(){
-                                    Entry::App(_, connection_value) => match aim_entry {
-                                        Entry::App(_, aim_value) => 
-                                            match Connection::try_from(connection_value.to_owned()) {
-                                                Ok(connection) => 
-                                                    match Aim::try_from(aim_value.to_owned()) {
-                                                        Ok(aim) => Some(ConnectedAim {
-                                                            aim, 
-                                                            aim_address: aim_address.clone().into(), 
-                                                            connection, 
-                                                            connection_address: connection_address.clone().into()
-                                                        }), 
-                                                        Err(_) => None
-                                                    }
-                                                Err(_) => None
-                                            },
-                                        
-                                        _ => None
-                                    }, 
-                                    _ => None
-                                }, 
-                                None => None
-                            },
-                            Err(_) => None
-                        })
-                        .next(),
-                    Err(_) => None
-                } {
-                    Some(connected_aim) => connected_aim, 
-                    None => None
-                },
-                None => None
+    )?.addresses().into_iter().next()
+        .ok_or_else(|| AimsError::BrokenLink {
+            address: connection.address.clone(),
+            link_type,
+        })?;
+    Ok(ConnectedAim {
+        aim: load_app_entry(&aim_address, "aim")?,
+        aim_address,
+        connection: connection.entry,
+        connection_address: connection.address,
+    })
+}
+
+/// Follows the connections of an aim in the given relation, leaving out archived
+/// aims. Connections that cannot be followed are reported in `errors`.
+pub fn load_connected_aims(aim_address: &Address, relation: &str) -> AimsResult<ConnectedAims> {
+    let connections = load_linked_entries::<Connection>(aim_address, &format!("{}_connection", relation))?;
+    let mut connected_aims = ConnectedAims {
+        aims: Vec::new(),
+        errors: connections.failures.into_iter().map(AddressError::from).collect(),
+    };
+    for connection in connections.entries {
+        let connection_address = connection.address.clone();
+        match load_connected_aim(connection, relation) {
+            Ok(connected_aim) => if !is_archived(&connected_aim.aim_address)? {
+                connected_aims.aims.push(connected_aim);
             },
-            Err(_) => None
-        })
-        .collect::<Vec<ConnectedAim>>() 
-    )
+            Err(error) => connected_aims.errors.push(AddressError {
+                address: connection_address,
+                error,
+            }),
+        }
+    }
+    Ok(connected_aims)
+}
+
+/// The connected aims only, for changes that have to see every connection. Fails
+/// with the first connection that cannot be followed.
+pub fn get_connected_aims (
+    aim_address: Address, 
+    relation: String
+) -> AimsResult<Vec<ConnectedAim>> {
+    let connected_aims = load_connected_aims(&aim_address, &relation)?;
+    match connected_aims.errors.into_iter().next() {
+        Some(failure) => Err(failure.error),
+        None => Ok(connected_aims.aims),
+    }
 }
 
 fn aim_entry_definition() -> ValidatingEntryType {
@@ -361,18 +689,43 @@ fn aim_entry_definition() -> ValidatingEntryType {
             hdk::ValidationPackageDefinition::Entry
         },
 
-        validation: | _validation_data: hdk::EntryValidationData<Aim>| {
-            Ok(())
+        validation: | validation_data: hdk::EntryValidationData<Aim>| {
+            match validation_data {
+                hdk::EntryValidationData::Create { entry, validation_data } => {
+                    validate_root_of(None, &entry, &validation_data.sources())?;
+                    validate_aim_content(&entry, &validation_data.package.chain_header)
+                },
+                hdk::EntryValidationData::Modify { new_entry, old_entry, old_entry_header, validation_data } => {
+                    validate_can_maintain(&old_entry_header, &validation_data.sources())?;
+                    validate_root_of(Some(&old_entry), &new_entry, &validation_data.sources())?;
+                    validate_status_change(&old_entry, &new_entry, &validation_data.sources())?;
+                    validate_aim_content(&new_entry, &validation_data.package.chain_header)
+                },
+                hdk::EntryValidationData::Delete { old_entry_header, validation_data, .. } => {
+                    validate_deletion(&old_entry_header, &validation_data.sources())
+                }
+            }
         },
 		links: [
 			from!(
 				EntryType::AgentId, 
 				link_type: "has_root_aim", 
 				validation_package:  || {
-					hdk::ValidationPackageDefinition::Entry
+					hdk::ValidationPackageDefinition::ChainFull
 				},
-				validation: | _validation_data: hdk::LinkValidationData | {
-					Ok(())
+				validation: | validation_data: hdk::LinkValidationData | {
+                    match validation_data {
+                        hdk::LinkValidationData::LinkAdd { link, validation_data } => {
+                            validate_root_aim_link(link.link.base(), link.link.target(), &validation_data.sources(), &validation_data.package.source_chain_entries)
+                        },
+                        hdk::LinkValidationData::LinkRemove { link, validation_data } => {
+                            if validation_data.sources().contains(link.link.base()) {
+                                Ok(())
+                            } else {
+                                validate_aim_link_removal(link.link.target(), &validation_data.sources())
+                            }
+                        }
+                    }
 				}
 			),
 			from!(
@@ -381,14 +734,26 @@ fn aim_entry_definition() -> ValidatingEntryType {
 				validation_package:  || {
 					hdk::ValidationPackageDefinition::Entry
 				},
-				validation: | _validation_data: hdk::LinkValidationData | {
-					Ok(())
+				validation: | validation_data: hdk::LinkValidationData | {
+                    match validation_data {
+                        hdk::LinkValidationData::LinkRemove { link, validation_data } => {
+                            validate_aim_link_removal(link.link.target(), &validation_data.sources())
+                        },
+                        _ => Ok(())
+                    }
 				}
 			)
 		]
     )
 }
 
+fn validate_connection_link_consent(connection_address: &Address, sources: &[Address]) -> Result<(), String> {
+    match sources.first() {
+        Some(link_author) => validate_connection_consent(connection_address, link_author),
+        None => Err("link has no author".into())
+    }
+}
+
 fn connection_entry_definition() -> ValidatingEntryType {
     entry!(
         name: "connection", 
@@ -397,8 +762,19 @@ fn connection_entry_definition() -> ValidatingEntryType {
         validation_package: || {
             hdk::ValidationPackageDefinition::Entry
         },
-        validation: | _validation_data: hdk::EntryValidationData<Connection>| {
-            Ok(())
+        validation: | validation_data: hdk::EntryValidationData<Connection>| {
+            match validation_data {
+                hdk::EntryValidationData::Create { entry, .. } => {
+                    validate_connection_content(&entry)
+                },
+                hdk::EntryValidationData::Modify { new_entry, old_entry, validation_data, .. } => {
+                    validate_connection_update(&old_entry, &new_entry, &validation_data.sources())?;
+                    validate_connection_content(&new_entry)
+                },
+                hdk::EntryValidationData::Delete { old_entry_header, validation_data, .. } => {
+                    validate_deletion(&old_entry_header, &validation_data.sources())
+                }
+            }
         },
 		links: [
 			from!(
@@ -407,8 +783,13 @@ fn connection_entry_definition() -> ValidatingEntryType {
 				validation_package:  || {
 					hdk::ValidationPackageDefinition::Entry
 				},
-				validation: | _validation_data: hdk::LinkValidationData | {
-					Ok(())
+				validation: | validation_data: hdk::LinkValidationData | {
+                    match validation_data {
+                        hdk::LinkValidationData::LinkRemove { link, validation_data } => {
+                            validate_connection_link_removal(link.link.target(), &validation_data.sources())
+                        },
+                        _ => Ok(())
+                    }
 				}
 			), 
 			from!(
@@ -417,8 +798,15 @@ fn connection_entry_definition() -> ValidatingEntryType {
 				validation_package:  || {
 					hdk::ValidationPackageDefinition::Entry
 				},
-				validation: | _validation_data: hdk::LinkValidationData | {
-					Ok(())
+				validation: | validation_data: hdk::LinkValidationData | {
+                    match validation_data {
+                        hdk::LinkValidationData::LinkAdd { link, validation_data } => {
+                            validate_connection_link_consent(link.link.target(), &validation_data.sources())
+                        },
+                        hdk::LinkValidationData::LinkRemove { link, validation_data } => {
+                            validate_connection_link_removal(link.link.target(), &validation_data.sources())
+                        }
+                    }
 				}
 			), 
 			to!(
@@ -427,8 +815,15 @@ fn connection_entry_definition() -> ValidatingEntryType {
 				validation_package:  || {
 					hdk::ValidationPackageDefinition::Entry
 				},
-				validation: | _validation_data: hdk::LinkValidationData | {
-					Ok(())
+				validation: | validation_data: hdk::LinkValidationData | {
+                    match validation_data {
+                        hdk::LinkValidationData::LinkAdd { link, validation_data } => {
+                            validate_connection_link_consent(link.link.base(), &validation_data.sources())
+                        },
+                        hdk::LinkValidationData::LinkRemove { link, validation_data } => {
+                            validate_connection_link_removal(link.link.base(), &validation_data.sources())
+                        }
+                    }
 				}
 			), 
 			to!(
@@ -437,18 +832,102 @@ fn connection_entry_definition() -> ValidatingEntryType {
 				validation_package:  || {
 					hdk::ValidationPackageDefinition::Entry
 				},
-				validation: | _validation_data: hdk::LinkValidationData | {
-					Ok(())
+				validation: | validation_data: hdk::LinkValidationData | {
+                    match validation_data {
+                        hdk::LinkValidationData::LinkRemove { link, validation_data } => {
+                            validate_connection_link_removal(link.link.base(), &validation_data.sources())
+                        },
+                        _ => Ok(())
+                    }
 				}
 			)
 		]
     )
 }
 
+fn anchor_entry_definition() -> ValidatingEntryType {
+    entry!(
+        name: "anchor",
+        description: "a well known entry that other entries can be linked from, so they can be found by everyone",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: | _validation_data: hdk::EntryValidationData<Anchor>| {
+            Ok(())
+        },
+        links: [
+            to!(
+                "aim",
+                link_type: "root_aim",
+                validation_package:  || {
+                    hdk::ValidationPackageDefinition::ChainFull
+                },
+                validation: | validation_data: hdk::LinkValidationData | {
+                    match validation_data {
+                        hdk::LinkValidationData::LinkAdd { link, validation_data } => {
+                            validate_root_aim_registration(link.link.target(), &validation_data.sources(), &validation_data.package.source_chain_entries)
+                        },
+                        hdk::LinkValidationData::LinkRemove { link, validation_data } => {
+                            validate_root_aim_unregistration(link.link.target(), &validation_data.sources())
+                        }
+                    }
+                }
+            ),
+            from!(
+                "aim",
+                link_type: "is_archived",
+                validation_package:  || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: | validation_data: hdk::LinkValidationData | {
+                    match validation_data {
+                        hdk::LinkValidationData::LinkAdd { link, validation_data } => {
+                            validate_archiving(link.link.base(), &validation_data.sources())
+                        },
+                        hdk::LinkValidationData::LinkRemove { link, validation_data } => {
+                            validate_archiving(link.link.base(), &validation_data.sources())
+                        }
+                    }
+                }
+            ),
+            to!(
+                "aim",
+                link_type: "tagged_aim",
+                validation_package:  || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: | validation_data: hdk::LinkValidationData | {
+                    match validation_data {
+                        hdk::LinkValidationData::LinkRemove { link, validation_data } => {
+                            validate_tag_link_removal(link.link.target(), &validation_data.sources())
+                        },
+                        _ => Ok(())
+                    }
+                }
+            ),
+            to!(
+                "anchor",
+                link_type: "has_tag",
+                validation_package:  || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: | _validation_data: hdk::LinkValidationData | {
+                    Ok(())
+                }
+            )
+        ]
+    )
+}
+
 define_zome! {
     entries: [
        aim_entry_definition(), 
-       connection_entry_definition()
+       connection_entry_definition(),
+       anchor_entry_definition(),
+       connection_proposal_entry_definition(),
+       membership_entry_definition(),
+       comment_entry_definition()
     ]
 
     init: || { 
@@ -462,25 +941,29 @@ define_zome! {
         Ok(())
     }
 
+    receive: |from, message| {
+        handle_receive(from, message)
+    }
+
     functions: [
 		get_agent_address: {
 			inputs: | |, 
-			outputs: |address: ZomeApiResult<Address>|, 
+			outputs: |address: AimsResult<Address>|, 
 			handler: handle_get_agent_address
 		}
         create_root_aim: {
             inputs: | |,
-            outputs: |address: ZomeApiResult<Address>|,
+            outputs: |address: AimsResult<Address>|,
             handler: handle_create_root_aim 
         }
         get_root_aim_address_or_create: {
             inputs: | |,
-            outputs: |address: ZomeApiResult<Address>|,
+            outputs: |address: AimsResult<Address>|,
             handler: handle_get_root_aim_address_or_create
         }
         get_root_aim_address: {
             inputs: | |,
-            outputs: |address: ZomeApiResult<Option<Address>>|,
+            outputs: |address: AimsResult<Option<Address>>|,
             handler: handle_get_root_aim_address
         }
         create_aim: {
@@ -492,7 +975,7 @@ define_zome! {
                 color: [char; 6],
                 tags: Vec<String>
             |,
-            outputs: |result: ZomeApiResult<Address>|,
+            outputs: |result: AimsResult<Address>|,
             handler: handle_create_aim
         }
         create_receiving_aim: {
@@ -505,7 +988,7 @@ define_zome! {
                 tags: Vec<String>, 
                 connected_aim_address: Address
             |,
-            outputs: |result: ZomeApiResult<Address>|,
+            outputs: |result: AimsResult<Address>|,
             handler: handle_create_receiving_aim
         }
         create_contributing_aim: {
@@ -518,7 +1001,7 @@ define_zome! {
                 tags: Vec<String>, 
                 connected_aim_address: Address
             |,
-            outputs: |result: ZomeApiResult<Address>|,
+            outputs: |result: AimsResult<Address>|,
             handler: handle_create_contributing_aim
         }
         update_aim: {
@@ -531,40 +1014,251 @@ define_zome! {
                 color: [char; 6],
                 tags: Vec<String>
             |,
-            outputs: |result: ZomeApiResult<Address>|,
+            outputs: |result: AimsResult<Address>|,
             handler: handle_update_aim
         }
         get_aims: {
-            inputs: | |,
-            outputs: |result: ZomeApiResult<Vec<Aim>>|,
+            inputs: |order: Option<AimOrder>, descending: Option<bool>, offset: Option<u32>, limit: Option<u32>|,
+            outputs: |result: AimsResult<AimList>|,
             handler: handle_get_aims 
         }
+        get_aims_of_agent: {
+            inputs: |agent_address: Address, order: Option<AimOrder>, descending: Option<bool>, offset: Option<u32>, limit: Option<u32>|,
+            outputs: |result: AimsResult<AimList>|,
+            handler: handle_get_aims_of_agent
+        }
         get_aim_details: {
             inputs: | aim_address: Address |,
-            outputs: | result: ZomeApiResult<AimDetails> |, 
+            outputs: | result: AimsResult<AimDetails> |, 
             handler: handle_get_aim_details 
         }
         create_connection: {
-            inputs: |contributing_aim_address: Address, receiving_aim_address: Address, contribution: u32 |,
-            outputs: |result: ZomeApiResult<()>|,
+            inputs: |contributing_aim_address: Address, receiving_aim_address: Address, contribution: u32, merge_policy: Option<MergePolicy> |,
+            outputs: |result: AimsResult<CreatedConnection>|,
             handler: handle_create_connection 
         }
         get_contributing_aims: {
             inputs: |aim_address: Address|,
-            outputs: |result: ZomeApiResult<Vec<ConnectedAim>>|, 
+            outputs: |result: AimsResult<ConnectedAims>|, 
             handler: handle_get_contributing_aims
         }
         get_receiving_aims: {
             inputs: |aim_address: Address|,
-            outputs: |result: ZomeApiResult<Vec<ConnectedAim>>|, 
+            outputs: |result: AimsResult<ConnectedAims>|, 
             handler: handle_get_receiving_aims
         }
+        compute_importance_map: {
+            inputs: | |,
+            outputs: |result: AimsResult<ImportanceMap>|,
+            handler: handle_compute_importance_map
+        }
+        get_aim_importance: {
+            inputs: |aim_address: Address|,
+            outputs: |result: AimsResult<AimImportanceResult>|,
+            handler: handle_get_aim_importance
+        }
+        propose_connection: {
+            inputs: |contributing_aim_address: Address, receiving_aim_address: Address, contribution: u32, timestamp_ms: i64|,
+            outputs: |result: AimsResult<Address>|,
+            handler: handle_propose_connection
+        }
+        get_connection_proposals: {
+            inputs: |aim_address: Address|,
+            outputs: |result: AimsResult<ProposalList>|,
+            handler: handle_get_connection_proposals
+        }
+        accept_connection_proposal: {
+            inputs: |proposal_address: Address|,
+            outputs: |result: AimsResult<CreatedConnection>|,
+            handler: handle_accept_connection_proposal
+        }
+        decline_connection_proposal: {
+            inputs: |proposal_address: Address|,
+            outputs: |result: AimsResult<()>|,
+            handler: handle_decline_connection_proposal
+        }
+        delete_connection: {
+            inputs: |connection_address: Address|,
+            outputs: |result: AimsResult<()>|,
+            handler: handle_delete_connection
+        }
+        delete_aim: {
+            inputs: |aim_address: Address, cascade: bool|,
+            outputs: |result: AimsResult<()>|,
+            handler: handle_delete_aim
+        }
+        archive_aim: {
+            inputs: |aim_address: Address|,
+            outputs: |result: AimsResult<()>|,
+            handler: handle_archive_aim
+        }
+        unarchive_aim: {
+            inputs: |aim_address: Address|,
+            outputs: |result: AimsResult<()>|,
+            handler: handle_unarchive_aim
+        }
+        get_archived_aims: {
+            inputs: | |,
+            outputs: |result: AimsResult<Vec<Address>>|,
+            handler: handle_get_archived_aims
+        }
+        get_subtree_effort: {
+            inputs: |aim_address: Address, weighted: bool|,
+            outputs: |result: AimsResult<SubtreeEffort>|,
+            handler: handle_get_subtree_effort
+        }
+        find_cycles: {
+            inputs: | |,
+            outputs: |result: AimsResult<FoundCycles>|,
+            handler: handle_find_cycles
+        }
+        get_aims_by_tag: {
+            inputs: |tag: String, scope: TagScope|,
+            outputs: |result: AimsResult<Vec<Address>>|,
+            handler: handle_get_aims_by_tag
+        }
+        get_aims_by_tags: {
+            inputs: |tags: Vec<String>, tag_match: TagMatch, scope: TagScope|,
+            outputs: |result: AimsResult<Vec<Address>>|,
+            handler: handle_get_aims_by_tags
+        }
+        list_tags_with_counts: {
+            inputs: |scope: TagScope|,
+            outputs: |result: AimsResult<Vec<TagCount>>|,
+            handler: handle_list_tags_with_counts
+        }
+        get_aim_tree: {
+            inputs: |aim_address: Address, direction: Direction, max_depth: u32|,
+            outputs: |result: AimsResult<AimTree>|,
+            handler: handle_get_aim_tree
+        }
+        export_graph: {
+            inputs: |aim_address: Option<Address>, format: ExportFormat, max_depth: Option<u32>|,
+            outputs: |result: AimsResult<GraphExport>|,
+            handler: handle_export_graph
+        }
+        import_outline: {
+            inputs: |content: String, format: OutlineFormat, parent_aim_address: Address, dry_run: bool, timestamp_ms: i64|,
+            outputs: |result: AimsResult<ImportReport>|,
+            handler: handle_import_outline
+        }
+        get_paths_to_root: {
+            inputs: |aim_address: Address, max_paths: Option<u32>|,
+            outputs: |result: AimsResult<AimPaths>|,
+            handler: handle_get_paths_to_root
+        }
+        set_aim_status: {
+            inputs: |aim_address: Address, status: AimStatus, progress: Option<u8>, timestamp_ms: i64|,
+            outputs: |result: AimsResult<Address>|,
+            handler: handle_set_aim_status
+        }
+        get_aim_completion: {
+            inputs: |aim_address: Address|,
+            outputs: |result: AimsResult<AimCompletion>|,
+            handler: handle_get_aim_completion
+        }
+        assign_role: {
+            inputs: |aim_address: Address, agent_address: Address, role: Role|,
+            outputs: |result: AimsResult<Address>|,
+            handler: handle_assign_role
+        }
+        invite_member: {
+            inputs: |aim_address: Address, agent_address: Address, role: Role|,
+            outputs: |result: AimsResult<Address>|,
+            handler: handle_invite_member
+        }
+        get_invitations: {
+            inputs: | |,
+            outputs: |result: AimsResult<InvitationList>|,
+            handler: handle_get_invitations
+        }
+        accept_invitation: {
+            inputs: |invitation_address: Address|,
+            outputs: |result: AimsResult<Address>|,
+            handler: handle_accept_invitation
+        }
+        revoke_role: {
+            inputs: |aim_address: Address, agent_address: Address|,
+            outputs: |result: AimsResult<RevokedRoles>|,
+            handler: handle_revoke_role
+        }
+        add_comment: {
+            inputs: |target_address: Address, parent_comment_address: Option<Address>, text: String, timestamp_ms: i64|,
+            outputs: |result: AimsResult<Address>|,
+            handler: handle_add_comment
+        }
+        edit_comment: {
+            inputs: |comment_address: Address, text: String, timestamp_ms: i64|,
+            outputs: |result: AimsResult<Address>|,
+            handler: handle_edit_comment
+        }
+        delete_comment: {
+            inputs: |comment_address: Address|,
+            outputs: |result: AimsResult<()>|,
+            handler: handle_delete_comment
+        }
+        get_comments: {
+            inputs: |target_address: Address, offset: Option<u32>, limit: Option<u32>|,
+            outputs: |result: AimsResult<CommentList>|,
+            handler: handle_get_comments
+        }
+        get_aim_history: {
+            inputs: |aim_address: Address|,
+            outputs: |result: AimsResult<Vec<AimVersion>>|,
+            handler: handle_get_aim_history
+        }
+        diff_aim_versions: {
+            inputs: |from_version: Address, to_version: Address|,
+            outputs: |result: AimsResult<AimDiff>|,
+            handler: handle_diff_aim_versions
+        }
+        revert_aim: {
+            inputs: |aim_address: Address, version_address: Address|,
+            outputs: |result: AimsResult<Address>|,
+            handler: handle_revert_aim
+        }
+        update_connection_contribution: {
+            inputs: |connection_address: Address, contribution: u32|,
+            outputs: |result: AimsResult<Address>|,
+            handler: handle_update_connection_contribution
+        }
+        set_contribution_weights: {
+            inputs: |receiving_aim_address: Address, weights: HashMap<Address, f64>|,
+            outputs: |result: AimsResult<Vec<ContributionWeight>>|,
+            handler: handle_set_contribution_weights
+        }
+        merge_duplicate_connections: {
+            inputs: |sum_contributions: bool|,
+            outputs: |result: AimsResult<Vec<MergedConnections>>|,
+            handler: handle_merge_duplicate_connections
+        }
+        replace_root_aim: {
+            inputs: |new_root_aim_address: Address, move_contributors_to_new_root: bool|,
+            outputs: |result: AimsResult<Address>|,
+            handler: handle_replace_root_aim
+        }
+        repair_root_aims: {
+            inputs: |survivor: Option<Address>|,
+            outputs: |result: AimsResult<RootRepair>|,
+            handler: handle_repair_root_aims
+        }
+        customize_root_aim: {
+            inputs: |title: String, description: String, color: [char; 6]|,
+            outputs: |result: AimsResult<Address>|,
+            handler: handle_customize_root_aim
+        }
+        migrate_root_aim: {
+            inputs: | |,
+            outputs: |result: AimsResult<Address>|,
+            handler: handle_migrate_root_aim
+        }
     ]
 
     traits: {
         hc_public [
 			create_aim, 
 			get_aims,
+            get_aims_of_agent,
             get_aim_details,
             get_agent_address,
             get_root_aim_address, 
@@ -575,7 +1269,48 @@ define_zome! {
             update_aim,
             create_connection, 
             get_contributing_aims,
-            get_receiving_aims
+            get_receiving_aims,
+            compute_importance_map,
+            get_aim_importance,
+            propose_connection,
+            get_connection_proposals,
+            accept_connection_proposal,
+            decline_connection_proposal,
+            delete_connection,
+            delete_aim,
+            archive_aim,
+            unarchive_aim,
+            get_archived_aims,
+            get_subtree_effort,
+            find_cycles,
+            get_aims_by_tag,
+            get_aims_by_tags,
+            list_tags_with_counts,
+            get_aim_tree,
+            export_graph,
+            import_outline,
+            get_paths_to_root,
+            set_aim_status,
+            get_aim_completion,
+            assign_role,
+            invite_member,
+            get_invitations,
+            accept_invitation,
+            revoke_role,
+            add_comment,
+            edit_comment,
+            delete_comment,
+            get_comments,
+            get_aim_history,
+            diff_aim_versions,
+            revert_aim,
+            update_connection_contribution,
+            set_contribution_weights,
+            merge_duplicate_connections,
+            replace_root_aim,
+            repair_root_aims,
+            customize_root_aim,
+            migrate_root_aim
 		]
     }
 }
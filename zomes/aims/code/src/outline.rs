@@ -0,0 +1,111 @@
+use aim_graph::outline::{
+    parse_markdown,
+    parse_opml,
+    validate_outline,
+    OutlineNode,
+};
+
+use hdk::holochain_persistence_api::{
+    cas::content::Address,
+};
+
+use hdk::holochain_json_api::{
+    error::JsonError,
+    json::JsonString,
+};
+
+use crate::{
+    Effort,
+    errors::{
+        AimsError,
+        AimsResult,
+    },
+    handle_create_contributing_aim,
+    properties::effort_scale,
+};
+
+/// Effort of imported aims that do not state one.
+pub const DEFAULT_IMPORT_EFFORT: Effort = Effort::Days(1);
+pub const DEFAULT_IMPORT_COLOR: [char; 6] = ['5'; 6];
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OutlineFormat {
+    Markdown,
+    Opml,
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct ImportedAim {
+    title: String,
+    effort: Effort,
+    tags: Vec<String>,
+    depth: u32,
+    /// Not set on a dry run.
+    address: Option<Address>,
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct ImportReport {
+    dry_run: bool,
+    aims: Vec<ImportedAim>,
+}
+
+fn import_nodes(
+    nodes: &[OutlineNode],
+    parent: Option<&Address>,
+    depth: u32,
+    dry_run: bool,
+    timestamp_ms: i64,
+    report: &mut Vec<ImportedAim>,
+) -> AimsResult<()> {
+    for node in nodes {
+        let effort = node.effort.clone().unwrap_or(DEFAULT_IMPORT_EFFORT);
+        let address = match (dry_run, parent) {
+            (false, Some(parent)) => Some(handle_create_contributing_aim(
+                node.title.clone(),
+                String::new(),
+                effort.clone(),
+                timestamp_ms,
+                DEFAULT_IMPORT_COLOR,
+                node.tags.clone(),
+                parent.clone()
+            )?),
+            _ => None,
+        };
+        report.push(ImportedAim {
+            title: node.title.clone(),
+            effort,
+            tags: node.tags.clone(),
+            depth,
+            address: address.clone(),
+        });
+        import_nodes(&node.children, address.as_ref().or(parent), depth + 1, dry_run, timestamp_ms, report)?;
+    }
+    Ok(())
+}
+
+/// Creates one aim per outline item, each contributing to the aim of the item it
+/// is nested in. Top level items contribute to `parent_aim_address`. All items are
+/// validated first; on a dry run nothing is committed and the report lists what
+/// would be created.
+pub fn handle_import_outline(
+    content: String,
+    format: OutlineFormat,
+    parent_aim_address: Address,
+    dry_run: bool,
+    timestamp_ms: i64,
+) -> AimsResult<ImportReport> {
+    let scale = effort_scale();
+    let nodes = match format {
+        OutlineFormat::Markdown => parse_markdown(&content, &scale),
+        OutlineFormat::Opml => parse_opml(&content, &scale).map_err(AimsError::invalid_input)?,
+    };
+    validate_outline(&nodes, &DEFAULT_IMPORT_EFFORT, &scale).map_err(AimsError::invalid_input)?;
+    let mut aims = Vec::new();
+    import_nodes(&nodes, Some(&parent_aim_address), 0, dry_run, timestamp_ms, &mut aims)?;
+    Ok(ImportReport {
+        dry_run,
+        aims,
+    })
+}
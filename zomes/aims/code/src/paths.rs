@@ -0,0 +1,68 @@
+use aim_graph::paths::paths_to;
+
+use hdk::holochain_persistence_api::{
+    cas::content::Address,
+};
+
+use hdk::holochain_json_api::{
+    error::JsonError,
+    json::JsonString,
+};
+
+use crate::{
+    errors::{
+        AddressError,
+        AimsError,
+        AimsResult,
+    },
+    graph::HdkStore,
+    handle_get_root_aim_address,
+};
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct PathStep {
+    aim_address: Address,
+    title: String,
+}
+
+/// A chain of aims from the root aim down to the requested aim. The weight is the
+/// product of the contributions of the connections along the way.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct AimPath {
+    steps: Vec<PathStep>,
+    weight: f64,
+}
+
+/// The paths to the root aim. Connections that could not be followed on the way
+/// are listed in `errors`, paths through them are missing. `incomplete` tells that
+/// the search gave up early on a graph with too many connections.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct AimPaths {
+    paths: Vec<AimPath>,
+    incomplete: bool,
+    errors: Vec<AddressError>,
+}
+
+/// Finds the ways from the agent's root aim to the given aim by following the
+/// connections upwards. With `max_paths` only the heaviest paths are returned.
+pub fn handle_get_paths_to_root(aim_address: Address, max_paths: Option<u32>) -> AimsResult<AimPaths> {
+    let root_address = handle_get_root_aim_address()?
+        .ok_or_else(|| AimsError::conflict("this agent has no root aim"))?;
+    let store = HdkStore::new();
+    let found = paths_to(&store, &aim_address, &root_address, max_paths.map(|max_paths| max_paths as usize))?;
+    Ok(AimPaths {
+        paths: found.paths.into_iter()
+            .map(|path| AimPath {
+                steps: path.steps.into_iter()
+                    .map(|step| PathStep {
+                        aim_address: step.aim_id,
+                        title: step.title,
+                    })
+                    .collect(),
+                weight: path.weight,
+            })
+            .collect(),
+        incomplete: found.incomplete,
+        errors: store.into_errors(),
+    })
+}
@@ -0,0 +1,59 @@
+use hdk::holochain_persistence_api::{
+    cas::content::Address,
+};
+
+use hdk::holochain_json_api::{
+    error::JsonError,
+    json::JsonString,
+};
+
+use serde_json::json;
+
+use entries::LoadFailure;
+
+use crate::{
+    AddressAndEntry,
+    errors::{
+        AddressError,
+        AimsError,
+        AimsResult,
+    },
+};
+
+/// A profile as stored by the profiles zome.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct Profile {
+    name: String,
+    creator: Address,
+}
+
+/// What the profiles zome returns for the profiles of an agent.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+struct ProfileList {
+    profiles: Vec<AddressAndEntry<Profile>>,
+    errors: Vec<LoadFailure>,
+}
+
+/// Loads the profiles of an agent through the profiles zome of this instance.
+/// Profiles that could not be loaded are added to `errors`.
+pub fn get_profiles_of_agent(agent_address: &Address, errors: &mut Vec<AddressError>) -> AimsResult<Vec<AddressAndEntry<Profile>>> {
+    let response = hdk::call(
+        hdk::THIS_INSTANCE,
+        "profiles",
+        Address::from(hdk::PUBLIC_TOKEN.to_string()),
+        "get_agent_profiles",
+        json!({ "agent_address": agent_address }).into()
+    )?;
+    let result: Result<ProfileList, serde_json::Value> =
+        serde_json::from_str(&String::from(response))
+            .map_err(|_| AimsError::Remote {
+                zome: "profiles".into(),
+                reason: "could not parse the profiles of an agent".into(),
+            })?;
+    let list = result.map_err(|err| AimsError::Remote {
+        zome: "profiles".into(),
+        reason: format!("could not load the profiles of an agent: {}", err),
+    })?;
+    errors.extend(list.errors.into_iter().map(AddressError::from));
+    Ok(list.profiles)
+}
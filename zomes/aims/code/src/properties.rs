@@ -0,0 +1,93 @@
+use hdk::holochain_json_api::{
+    error::JsonError,
+    json::JsonString,
+};
+
+use std::convert::TryFrom;
+
+use aim_graph::{
+    Effort,
+    EffortScale,
+};
+
+/// Who may delete aims and connections. Configured through the
+/// `deletion_policy` DNA property; the history of deleted entries stays on the
+/// DHT either way.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeletionPolicy {
+    Anyone,
+    Author,
+}
+
+pub fn deletion_policy() -> DeletionPolicy {
+    hdk::property("deletion_policy").ok()
+        .and_then(|json| DeletionPolicy::try_from(json).ok())
+        .unwrap_or(DeletionPolicy::Anyone)
+}
+
+/// What happens when a new connection would close a cycle in the contribution
+/// graph. Configured through the `cycle_policy` DNA property.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CyclePolicy {
+    Reject,
+    Warn,
+    AllowAcrossAgents,
+}
+
+pub fn cycle_policy() -> CyclePolicy {
+    hdk::property("cycle_policy").ok()
+        .and_then(|json| CyclePolicy::try_from(json).ok())
+        .unwrap_or(CyclePolicy::Reject)
+}
+
+fn numeric_property(name: &str) -> Option<u64> {
+    hdk::property(name).ok()
+        .and_then(|json| serde_json::from_str::<u64>(&String::from(json)).ok())
+}
+
+pub fn effort_scale() -> EffortScale {
+    let default = EffortScale::default();
+    EffortScale {
+        working_hours_per_day: numeric_property("working_hours_per_day")
+            .unwrap_or(default.working_hours_per_day),
+        working_days_per_week: numeric_property("working_days_per_week")
+            .unwrap_or(default.working_days_per_week),
+    }
+}
+
+fn string_property(name: &str) -> Option<String> {
+    hdk::property(name).ok()
+        .and_then(|json| serde_json::from_str::<String>(&String::from(json)).ok())
+}
+
+/// What a new root aim looks like before its agent customizes it. Configured
+/// through the `root_aim_title`, `root_aim_description`, `root_aim_color` and
+/// `root_aim_effort` DNA properties.
+pub struct RootAimDefaults {
+    pub title: String,
+    pub description: String,
+    pub color: [char; 6],
+    pub effort: Effort,
+}
+
+pub fn root_aim_defaults() -> RootAimDefaults {
+    let mut color = ['5'; 6];
+    if let Some(hex) = string_property("root_aim_color") {
+        let digits: Vec<char> = hex.trim_start_matches('#').chars().collect();
+        if digits.len() == 6 && digits.iter().all(|c| c.is_ascii_hexdigit()) {
+            color.copy_from_slice(&digits);
+        }
+    }
+    RootAimDefaults {
+        title: string_property("root_aim_title")
+            .unwrap_or_else(|| String::from("root aim")),
+        description: string_property("root_aim_description")
+            .unwrap_or_else(|| String::from("this is the single root aim of this agent. Some algorithms will use these root aims as the main source of collective will: using these aims as the only entrance of importance flow before calculating the absolute importance for each goal")),
+        color,
+        effort: hdk::property("root_aim_effort").ok()
+            .and_then(|json| serde_json::from_str::<Effort>(&String::from(json)).ok())
+            .unwrap_or(Effort::Years(100)),
+    }
+}
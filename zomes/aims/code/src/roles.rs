@@ -0,0 +1,449 @@
+use hdk::{
+    entry_definition::ValidatingEntryType,
+    prelude::{
+        EntryType,
+        LinkMatch,
+    },
+};
+use hdk::holochain_core_types::{
+    chain_header::ChainHeader,
+    entry::Entry,
+    dna::entry_types::Sharing,
+};
+use hdk::holochain_wasm_utils::api_serialization::get_entry::{
+    GetEntryOptions,
+    GetEntryResultType,
+    StatusRequestKind,
+};
+
+use hdk::holochain_persistence_api::{
+    cas::content::Address,
+};
+
+use hdk::holochain_json_api::{
+    error::JsonError,
+    json::JsonString,
+};
+
+use crate::{
+    AddressAndEntry,
+    deletion::validate_aim_link_removal,
+    errors::{
+        AddressError,
+        AimsError,
+        AimsResult,
+        load_app_entry,
+    },
+    get_entry_author,
+    profiles::{
+        get_profiles_of_agent,
+        Profile,
+    },
+};
+
+/// The roles an agent can have on an aim, from least to most privileged. The author
+/// of an aim is always its owner.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Observer,
+    Contributor,
+    Maintainer,
+    Owner,
+}
+
+impl Role {
+    pub fn can_maintain(self) -> bool {
+        self >= Role::Maintainer
+    }
+}
+
+/// Gives `agent` a role on `aim`. Memberships granted through an invitation only
+/// become active once the invited agent accepted them, by committing an accepted
+/// copy that refers to the invitation.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct Membership {
+    aim: Address,
+    agent: Address,
+    role: Role,
+    granted_by: Address,
+    accepted: bool,
+    invitation: Option<Address>,
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct Member {
+    agent: Address,
+    role: Role,
+    profiles: Vec<AddressAndEntry<Profile>>,
+}
+
+fn load_membership(membership_address: &Address) -> AimsResult<Membership> {
+    load_app_entry(membership_address, "membership")
+}
+
+/// The memberships linked from an aim, and the ones that could not be loaded.
+struct Memberships {
+    memberships: Vec<(Address, Membership)>,
+    errors: Vec<AddressError>,
+}
+
+fn memberships_of_aim(aim_address: &Address) -> AimsResult<Memberships> {
+    let mut memberships = Memberships {
+        memberships: Vec::new(),
+        errors: Vec::new(),
+    };
+    for membership_address in hdk::get_links(
+        aim_address,
+        LinkMatch::Exactly("has_member"),
+        LinkMatch::Any
+    )?.addresses() {
+        match load_membership(&membership_address) {
+            Ok(membership) => memberships.memberships.push((membership_address, membership)),
+            Err(error) => memberships.errors.push(AddressError {
+                address: membership_address,
+                error,
+            }),
+        }
+    }
+    Ok(memberships)
+}
+
+/// The role an agent currently holds on an aim, if any. Memberships that cannot be
+/// loaded grant nothing.
+pub fn role_of(aim_address: &Address, agent: &Address) -> AimsResult<Option<Role>> {
+    if get_entry_author(aim_address)? == *agent {
+        return Ok(Some(Role::Owner));
+    }
+    Ok(memberships_of_aim(aim_address)?.memberships.into_iter()
+        .filter(|(_, membership)| membership.accepted && membership.agent == *agent)
+        .map(|(_, membership)| membership.role)
+        .max())
+}
+
+pub fn can_maintain(aim_address: &Address, agent: &Address) -> AimsResult<bool> {
+    Ok(role_of(aim_address, agent)?.map_or(false, Role::can_maintain))
+}
+
+fn ensure_can_grant(aim_address: &Address, role: Role) -> AimsResult<()> {
+    match role_of(aim_address, &hdk::AGENT_ADDRESS)? {
+        Some(Role::Owner) => Ok(()),
+        Some(Role::Maintainer) if role < Role::Owner => Ok(()),
+        _ => Err(AimsError::unauthorized("you are not allowed to grant this role on this aim")),
+    }
+}
+
+fn commit_membership(membership: Membership) -> AimsResult<Address> {
+    let aim_address = membership.aim.clone();
+    let agent = membership.agent.clone();
+    let entry = Entry::App("membership".into(), membership.into());
+    let membership_address = hdk::commit_entry(&entry)?;
+    hdk::link_entries(&aim_address, &membership_address, "has_member", "")?;
+    hdk::link_entries(&agent, &membership_address, "member_of", "")?;
+    Ok(membership_address)
+}
+
+fn remove_membership(membership_address: &Address, membership: &Membership) -> AimsResult<()> {
+    hdk::remove_link(&membership.aim, membership_address, "has_member", "")?;
+    hdk::remove_link(&membership.agent, membership_address, "member_of", "")?;
+    Ok(())
+}
+
+/// Removes all memberships of an aim, for when it is deleted.
+pub fn remove_memberships(aim_address: &Address) -> AimsResult<()> {
+    for (membership_address, membership) in memberships_of_aim(aim_address)?.memberships {
+        remove_membership(&membership_address, &membership)?;
+    }
+    Ok(())
+}
+
+/// Gives an agent a role on an aim right away. Owners can grant every role,
+/// maintainers every role but owner.
+pub fn handle_assign_role(aim_address: Address, agent_address: Address, role: Role) -> AimsResult<Address> {
+    ensure_can_grant(&aim_address, role)?;
+    commit_membership(Membership {
+        aim: aim_address,
+        agent: agent_address,
+        role,
+        granted_by: hdk::AGENT_ADDRESS.clone(),
+        accepted: true,
+        invitation: None,
+    })
+}
+
+/// Offers an agent a role on an aim, which only takes effect once they accept it.
+pub fn handle_invite_member(aim_address: Address, agent_address: Address, role: Role) -> AimsResult<Address> {
+    ensure_can_grant(&aim_address, role)?;
+    commit_membership(Membership {
+        aim: aim_address,
+        agent: agent_address,
+        role,
+        granted_by: hdk::AGENT_ADDRESS.clone(),
+        accepted: false,
+        invitation: None,
+    })
+}
+
+/// The open invitations of this agent, with the ones that could not be loaded
+/// listed in `errors`.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct InvitationList {
+    invitations: Vec<AddressAndEntry<Membership>>,
+    errors: Vec<AddressError>,
+}
+
+pub fn handle_get_invitations() -> AimsResult<InvitationList> {
+    let mut list = InvitationList {
+        invitations: Vec::new(),
+        errors: Vec::new(),
+    };
+    for membership_address in hdk::get_links(
+        &hdk::AGENT_ADDRESS,
+        LinkMatch::Exactly("member_of"),
+        LinkMatch::Any
+    )?.addresses() {
+        match load_membership(&membership_address) {
+            Ok(membership) => if !membership.accepted {
+                list.invitations.push(AddressAndEntry {
+                    entry: membership,
+                    address: membership_address,
+                });
+            },
+            Err(error) => list.errors.push(AddressError {
+                address: membership_address,
+                error,
+            }),
+        }
+    }
+    Ok(list)
+}
+
+pub fn handle_accept_invitation(invitation_address: Address) -> AimsResult<Address> {
+    let invitation = load_membership(&invitation_address)?;
+    if invitation.agent != *hdk::AGENT_ADDRESS || invitation.accepted {
+        return Err(AimsError::unauthorized("this is not an open invitation for you"));
+    }
+    let membership_address = commit_membership(Membership {
+        accepted: true,
+        invitation: Some(invitation_address.clone()),
+        ..invitation.clone()
+    })?;
+    remove_membership(&invitation_address, &invitation)?;
+    Ok(membership_address)
+}
+
+/// Members can always give up their own memberships, owners can remove every
+/// membership and maintainers every membership but an owner's.
+fn may_remove(membership: &Membership, remover: &Address, remover_role: Option<Role>) -> bool {
+    membership.agent == *remover || match remover_role {
+        Some(Role::Owner) => true,
+        Some(Role::Maintainer) => membership.role < Role::Owner,
+        _ => false,
+    }
+}
+
+/// The memberships a revocation removed. Memberships of the aim that could not be
+/// loaded are listed in `errors`; they might belong to the agent and stay in place.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct RevokedRoles {
+    revoked: Vec<Address>,
+    errors: Vec<AddressError>,
+}
+
+/// Takes all roles of an agent on an aim away. Agents can always give up their own roles.
+pub fn handle_revoke_role(aim_address: Address, agent_address: Address) -> AimsResult<RevokedRoles> {
+    let own_role = role_of(&aim_address, &hdk::AGENT_ADDRESS)?;
+    let memberships = memberships_of_aim(&aim_address)?;
+    let mut revoked = Vec::new();
+    for (membership_address, membership) in memberships.memberships {
+        if membership.agent != agent_address {
+            continue;
+        }
+        if !may_remove(&membership, &hdk::AGENT_ADDRESS, own_role) {
+            return Err(AimsError::unauthorized("you are not allowed to revoke this role"));
+        }
+        remove_membership(&membership_address, &membership)?;
+        revoked.push(membership_address);
+    }
+    Ok(RevokedRoles {
+        revoked,
+        errors: memberships.errors,
+    })
+}
+
+/// The owner and all agents holding an accepted role on the aim, each with their
+/// profiles from the profiles zome. Memberships and profiles that cannot be loaded
+/// are added to `errors`.
+pub fn get_members(aim_address: &Address, errors: &mut Vec<AddressError>) -> AimsResult<Vec<Member>> {
+    let mut members: Vec<Member> = vec![Member {
+        agent: get_entry_author(aim_address)?,
+        role: Role::Owner,
+        profiles: Vec::new(),
+    }];
+    let memberships = memberships_of_aim(aim_address)?;
+    errors.extend(memberships.errors);
+    for (_, membership) in memberships.memberships {
+        if !membership.accepted {
+            continue;
+        }
+        match members.iter_mut().find(|member| member.agent == membership.agent) {
+            Some(member) => member.role = member.role.max(membership.role),
+            None => members.push(Member {
+                agent: membership.agent,
+                role: membership.role,
+                profiles: Vec::new(),
+            }),
+        }
+    }
+    for member in members.iter_mut() {
+        member.profiles = get_profiles_of_agent(&member.agent, errors)?;
+    }
+    Ok(members)
+}
+
+/// Updates keep the links of an entry on its first version, so the memberships of
+/// an aim are found by following the update chain back to its start.
+pub fn original_entry_address(header: &ChainHeader) -> AimsResult<Address> {
+    let mut address = header.entry_address().clone();
+    let mut replaced = header.link_update_delete();
+    while let Some(previous) = replaced {
+        address = previous.clone();
+        let result = hdk::get_entry_result(
+            &previous,
+            GetEntryOptions::new(StatusRequestKind::Initial, false, true, Default::default())
+        )?;
+        replaced = match result.result {
+            GetEntryResultType::Single(item) => item.headers.first()
+                .and_then(|previous_header| previous_header.link_update_delete()),
+            _ => None,
+        };
+    }
+    Ok(address)
+}
+
+/// Only owners and maintainers of an aim may update it.
+pub fn validate_can_maintain(old_entry_header: &ChainHeader, sources: &[Address]) -> Result<(), String> {
+    let aim_address = original_entry_address(old_entry_header)
+        .map_err(|_| String::from("could not find the original version of this aim"))?;
+    for source in sources {
+        if can_maintain(&aim_address, source).unwrap_or(false) {
+            return Ok(());
+        }
+    }
+    Err("only owners and maintainers may change this aim".into())
+}
+
+fn validate_membership(membership: &Membership, sources: &[Address]) -> Result<(), String> {
+    if !sources.contains(&membership.granted_by) && membership.invitation.is_none() {
+        return Err("a membership has to be committed by the agent granting it".into());
+    }
+    if let Some(invitation_address) = &membership.invitation {
+        let invitation = load_membership(invitation_address)
+            .map_err(|_| String::from("could not load the accepted invitation"))?;
+        let matches = invitation.aim == membership.aim
+            && invitation.agent == membership.agent
+            && invitation.role == membership.role
+            && invitation.granted_by == membership.granted_by;
+        if !matches || !membership.accepted || !sources.contains(&membership.agent) {
+            return Err("an accepted membership has to match its invitation and be committed by the invited agent".into());
+        }
+    }
+    let granter_role = role_of(&membership.aim, &membership.granted_by)
+        .map_err(|_| String::from("could not determine the role of the granting agent"))?;
+    match granter_role {
+        Some(Role::Owner) => Ok(()),
+        Some(Role::Maintainer) if membership.role < Role::Owner => Ok(()),
+        _ => Err("the granting agent is not allowed to grant this role".into()),
+    }
+}
+
+/// Removing a membership, or one of its links, follows the same rules as
+/// `handle_revoke_role`, unless the aim itself is being deleted.
+fn validate_membership_removal(membership: &Membership, sources: &[Address]) -> Result<(), String> {
+    // whoever may delete the aim removes its memberships along with it
+    if validate_aim_link_removal(&membership.aim, sources).is_ok() {
+        return Ok(());
+    }
+    for source in sources {
+        let role = role_of(&membership.aim, source)
+            .map_err(|_| String::from("could not determine the role of the removing agent"))?;
+        if may_remove(membership, source, role) {
+            return Ok(());
+        }
+    }
+    Err("the removing agent is not allowed to revoke this membership".into())
+}
+
+/// A membership link has to start at the aim or the agent the membership is about.
+fn validate_membership_link(base: &Address, membership_address: &Address, remove: bool, sources: &[Address]) -> Result<(), String> {
+    let membership = load_membership(membership_address)
+        .map_err(|_| String::from("could not load the linked membership"))?;
+    if *base != membership.aim && *base != membership.agent {
+        return Err("a membership can only be linked from its aim and its agent".into());
+    }
+    if remove {
+        validate_membership_removal(&membership, sources)
+    } else {
+        Ok(())
+    }
+}
+
+pub fn membership_entry_definition() -> ValidatingEntryType {
+    entry!(
+        name: "membership",
+        description: "gives an agent a role on an aim",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: | validation_data: hdk::EntryValidationData<Membership>| {
+            match validation_data {
+                hdk::EntryValidationData::Create { entry, validation_data } => {
+                    validate_membership(&entry, &validation_data.sources())
+                },
+                hdk::EntryValidationData::Modify { .. } => {
+                    Err("memberships cannot be changed, revoke and assign them instead".into())
+                },
+                hdk::EntryValidationData::Delete { old_entry, validation_data, .. } => {
+                    validate_membership_removal(&old_entry, &validation_data.sources())
+                }
+            }
+        },
+        links: [
+            from!(
+                "aim",
+                link_type: "has_member",
+                validation_package:  || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: | validation_data: hdk::LinkValidationData | {
+                    match validation_data {
+                        hdk::LinkValidationData::LinkAdd { link, validation_data } => {
+                            validate_membership_link(link.link.base(), link.link.target(), false, &validation_data.sources())
+                        },
+                        hdk::LinkValidationData::LinkRemove { link, validation_data } => {
+                            validate_membership_link(link.link.base(), link.link.target(), true, &validation_data.sources())
+                        }
+                    }
+                }
+            ),
+            from!(
+                EntryType::AgentId,
+                link_type: "member_of",
+                validation_package:  || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: | validation_data: hdk::LinkValidationData | {
+                    match validation_data {
+                        hdk::LinkValidationData::LinkAdd { link, validation_data } => {
+                            validate_membership_link(link.link.base(), link.link.target(), false, &validation_data.sources())
+                        },
+                        hdk::LinkValidationData::LinkRemove { link, validation_data } => {
+                            validate_membership_link(link.link.base(), link.link.target(), true, &validation_data.sources())
+                        }
+                    }
+                }
+            )
+        ]
+    )
+}
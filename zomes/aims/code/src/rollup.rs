@@ -0,0 +1,82 @@
+use aim_graph::rollup::{
+    completion,
+    subtree_effort,
+};
+
+use hdk::holochain_persistence_api::{
+    cas::content::Address,
+};
+
+use hdk::holochain_json_api::{
+    error::JsonError,
+    json::JsonString,
+};
+
+use crate::{
+    Effort,
+    errors::{
+        AddressError,
+        AimsResult,
+    },
+    get_aim,
+    graph::HdkStore,
+    properties::effort_scale,
+    status::AimStatus,
+};
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct SubtreeEffort {
+    aim_address: Address,
+    own_effort: Effort,
+    contributing_effort: Effort,
+    total_effort: Effort,
+    contributing_aim_count: u32,
+    /// Connections that could not be followed; their aims are not counted.
+    errors: Vec<AddressError>,
+}
+
+/// Sums up the effort of all aims that transitively contribute to the given aim,
+/// optionally weighted by the contribution of the connections on the way.
+pub fn handle_get_subtree_effort(aim_address: Address, weighted: bool) -> AimsResult<SubtreeEffort> {
+    let store = HdkStore::new();
+    let totals = subtree_effort(&store, &aim_address, weighted, &effort_scale())?;
+    Ok(SubtreeEffort {
+        aim_address,
+        own_effort: totals.own_effort,
+        contributing_effort: totals.contributing_effort,
+        total_effort: totals.total_effort,
+        contributing_aim_count: totals.contributing_aim_count,
+        errors: store.into_errors(),
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct AimCompletion {
+    aim_address: Address,
+    status: AimStatus,
+    progress: u8,
+    contributing_effort: Effort,
+    done_effort: Effort,
+    /// Share of the contributing effort that is done, in percent. Abandoned aims do
+    /// not count as contributing effort.
+    completion: f64,
+    /// Connections that could not be followed; their aims are not counted.
+    errors: Vec<AddressError>,
+}
+
+/// Reports how much of the effort of all transitively contributing aims is done,
+/// counting each aim with its progress.
+pub fn handle_get_aim_completion(aim_address: Address) -> AimsResult<AimCompletion> {
+    let aim = get_aim(&aim_address)?;
+    let store = HdkStore::new();
+    let rollup = completion(&store, &aim_address, &effort_scale())?;
+    Ok(AimCompletion {
+        aim_address,
+        status: aim.status,
+        progress: aim.progress,
+        contributing_effort: rollup.contributing_effort,
+        done_effort: rollup.done_effort,
+        completion: rollup.completion,
+        errors: store.into_errors(),
+    })
+}
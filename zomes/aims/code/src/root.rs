@@ -0,0 +1,396 @@
+use hdk::{
+    prelude::LinkMatch,
+};
+
+use std::collections::HashSet;
+
+use chrono::DateTime;
+
+use hdk::holochain_core_types::entry::Entry;
+use hdk::holochain_wasm_utils::api_serialization::query::{
+    QueryArgsNames,
+    QueryArgsOptions,
+    QueryResult,
+};
+
+use hdk::holochain_persistence_api::{
+    cas::content::{
+        Address,
+        AddressableContent,
+    },
+};
+
+use hdk::holochain_json_api::{
+    error::JsonError,
+    json::JsonString,
+};
+
+use crate::{
+    Aim,
+    Effort,
+    commit_anchor,
+    deletion::{
+        handle_delete_connection,
+        validate_aim_link_removal,
+    },
+    duplicates::MergePolicy,
+    errors::{
+        AimsError,
+        AimsResult,
+    },
+    get_aim,
+    get_connected_aims,
+    get_entry_author,
+    handle_create_connection,
+    handle_create_root_aim,
+    handle_get_root_aim_address,
+    handle_update_aim,
+    properties::root_aim_defaults,
+    roles::can_maintain,
+    ROOT_AIMS_ANCHOR,
+    root_aims_anchor_address,
+    status::{
+        AimStatus,
+        StatusChange,
+    },
+};
+
+/// The creation timestamp of the root aim all agents shared before root aims were
+/// personalized.
+pub const SHARED_ROOT_AIM_TIMESTAMP_MS: i64 = 1594443995818;
+const SHARED_ROOT_AIM_TITLE: &str = "root aim";
+const SHARED_ROOT_AIM_DESCRIPTION: &str = "this is the single root aim of this agent. Some algorithms will use these root aims as the main source of collective will: using these aims as the only entrance of importance flow before calculating the absolute importance for each goal";
+
+/// The shared root aim as it was committed before aims had a status.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+struct SharedRootAim {
+    title: String,
+    description: String,
+    effort: Effort,
+    timestamp_ms: i64,
+    color: [char; 6],
+    tags: Vec<String>,
+}
+
+/// The shared root aim as it was committed once aims had a status.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+struct SharedRootAimWithStatus {
+    title: String,
+    description: String,
+    effort: Effort,
+    timestamp_ms: i64,
+    color: [char; 6],
+    tags: Vec<String>,
+    status: AimStatus,
+    progress: u8,
+    status_change: Option<StatusChange>,
+}
+
+/// Every agent used to commit the same root aim entry, so they all ended up with
+/// one shared entry. Its address follows from its content, for each version of the
+/// aim entry it was committed with.
+pub fn shared_root_aim_addresses() -> AimsResult<Vec<Address>> {
+    let shared = SharedRootAim {
+        title: SHARED_ROOT_AIM_TITLE.into(),
+        description: SHARED_ROOT_AIM_DESCRIPTION.into(),
+        effort: Effort::Years(100),
+        timestamp_ms: SHARED_ROOT_AIM_TIMESTAMP_MS,
+        color: ['5'; 6],
+        tags: Vec::new(),
+    };
+    let with_status = SharedRootAimWithStatus {
+        title: shared.title.clone(),
+        description: shared.description.clone(),
+        effort: shared.effort.clone(),
+        timestamp_ms: shared.timestamp_ms,
+        color: shared.color,
+        tags: Vec::new(),
+        status: AimStatus::Open,
+        progress: 0,
+        status_change: None,
+    };
+    Ok(vec![
+        hdk::entry_address(&Entry::App("aim".into(), shared.into()))?,
+        hdk::entry_address(&Entry::App("aim".into(), with_status.into()))?,
+    ])
+}
+
+/// Root aims are created during `init`, when the only clock at hand is the agent's
+/// own chain. They take the time the agent joined, which is when its first root
+/// aim came to be.
+fn agent_joined_ms() -> AimsResult<i64> {
+    let result = hdk::query_result(
+        QueryArgsNames::QueryName("%agent_id".into()),
+        QueryArgsOptions {
+            headers: true,
+            ..Default::default()
+        }
+    )?;
+    let header = match result {
+        QueryResult::Headers(headers) => headers.into_iter().next(),
+        _ => None,
+    }.ok_or_else(|| AimsError::Internal { reason: "the agent entry is missing from the source chain".into() })?;
+    let joined = DateTime::parse_from_rfc3339(&header.timestamp().to_string())
+        .map_err(|_| AimsError::Internal { reason: "could not read the header timestamp".into() })?;
+    Ok(joined.timestamp_millis())
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct RootRepair {
+    root_aim: Address,
+    removed_roots: Vec<Address>,
+    moved_connections: u32,
+}
+
+fn root_aim_addresses() -> AimsResult<Vec<Address>> {
+    let mut addresses: Vec<Address> = Vec::new();
+    for address in hdk::get_links(
+        &hdk::AGENT_ADDRESS,
+        LinkMatch::Exactly("has_root_aim"),
+        LinkMatch::Any
+    )?.addresses() {
+        if !addresses.contains(&address) {
+            addresses.push(address);
+        }
+    }
+    Ok(addresses)
+}
+
+/// The `has_root_aim` link comes first, the validation of the `root_aim` link
+/// looks for it.
+pub fn link_root_aim(aim_address: &Address) -> AimsResult<()> {
+    hdk::link_entries(&hdk::AGENT_ADDRESS, aim_address, "has_root_aim", "")?;
+    hdk::link_entries(&commit_anchor(ROOT_AIMS_ANCHOR)?, aim_address, "root_aim", "")?;
+    Ok(())
+}
+
+fn unlink_root_aim(aim_address: &Address) -> AimsResult<()> {
+    hdk::remove_link(&hdk::AGENT_ADDRESS, aim_address, "has_root_aim", "")?;
+    hdk::remove_link(&root_aims_anchor_address()?, aim_address, "root_aim", "")?;
+    Ok(())
+}
+
+/// Reconnects everything that contributes to `from` so it contributes to `to`
+/// instead, merging with connections `to` already has.
+fn move_contributors(from: &Address, to: &Address) -> AimsResult<u32> {
+    let mut moved = 0;
+    for connected in get_connected_aims(from.clone(), "receives_from".into())? {
+        if connected.aim_address != *to {
+            handle_create_connection(
+                connected.aim_address.clone(),
+                to.clone(),
+                connected.connection.contribution,
+                Some(MergePolicy::Sum)
+            )?;
+        }
+        handle_delete_connection(connected.connection_address)?;
+        moved += 1;
+    }
+    Ok(moved)
+}
+
+/// A new root aim for this agent, with the defaults from the DNA properties.
+pub fn personal_root_aim() -> AimsResult<Aim> {
+    let defaults = root_aim_defaults();
+    Ok(Aim {
+        title: defaults.title,
+        description: defaults.description,
+        effort: defaults.effort,
+        timestamp_ms: agent_joined_ms()?,
+        color: defaults.color,
+        tags: Vec::new(),
+        status: AimStatus::Open,
+        progress: 0,
+        status_change: None,
+        root_of: Some(hdk::AGENT_ADDRESS.clone()),
+    })
+}
+
+/// Gives this agent a root aim of its own if it still uses the root aim entry that
+/// all agents shared before root aims were personalized. The contributions of this
+/// agent's aims move over to the new root aim, which takes the place of the shared
+/// entry on the root aims anchor.
+pub fn handle_migrate_root_aim() -> AimsResult<Address> {
+    let shared_root_aim_address = match handle_get_root_aim_address()? {
+        Some(address) => address,
+        None => return handle_create_root_aim(),
+    };
+    if !shared_root_aim_addresses()?.contains(&shared_root_aim_address) {
+        return Ok(shared_root_aim_address);
+    }
+
+    let entry = Entry::App("aim".into(), personal_root_aim()?.into());
+    let root_aim_address = hdk::commit_entry(&entry)?;
+    unlink_root_aim(&shared_root_aim_address)?;
+    link_root_aim(&root_aim_address)?;
+
+    for connected in get_connected_aims(shared_root_aim_address, "receives_from".into())? {
+        if can_maintain(&connected.aim_address, &hdk::AGENT_ADDRESS)? {
+            handle_create_connection(
+                connected.aim_address.clone(),
+                root_aim_address.clone(),
+                connected.connection.contribution,
+                Some(MergePolicy::Sum)
+            )?;
+            handle_delete_connection(connected.connection_address)?;
+        }
+    }
+    Ok(root_aim_address)
+}
+
+/// Changes title, description and color of this agent's root aim, migrating it to
+/// a personal root aim first if needed.
+pub fn handle_customize_root_aim(title: String, description: String, color: [char; 6]) -> AimsResult<Address> {
+    let root_aim_address = handle_migrate_root_aim()?;
+    let root_aim = get_aim(&root_aim_address)?;
+    handle_update_aim(
+        root_aim_address,
+        title,
+        description,
+        root_aim.effort,
+        root_aim.timestamp_ms,
+        color,
+        root_aim.tags,
+    )
+}
+
+/// Makes one of this agent's aims its new root aim. The old root aim stays as a
+/// regular aim; with `move_contributors_to_new_root` its contributors are
+/// reconnected to the new root aim.
+pub fn handle_replace_root_aim(new_root_aim_address: Address, move_contributors_to_new_root: bool) -> AimsResult<Address> {
+    if get_entry_author(&new_root_aim_address)? != *hdk::AGENT_ADDRESS {
+        return Err(AimsError::unauthorized("only your own aims can become your root aim"));
+    }
+    for old_root_aim_address in root_aim_addresses()? {
+        if old_root_aim_address == new_root_aim_address {
+            continue;
+        }
+        unlink_root_aim(&old_root_aim_address)?;
+        if move_contributors_to_new_root {
+            move_contributors(&old_root_aim_address, &new_root_aim_address)?;
+        }
+    }
+    if !root_aim_addresses()?.contains(&new_root_aim_address) {
+        link_root_aim(&new_root_aim_address)?;
+    }
+    Ok(new_root_aim_address)
+}
+
+/// Collapses several root aims of this agent, left over from before a single root
+/// aim was enforced, into one. The survivor defaults to the first root aim found;
+/// the contributors of all others are reconnected to it.
+pub fn handle_repair_root_aims(survivor: Option<Address>) -> AimsResult<RootRepair> {
+    let root_aims = root_aim_addresses()?;
+    let root_aim = match survivor.or_else(|| root_aims.first().cloned()) {
+        Some(root_aim) => root_aim,
+        None => return Err(AimsError::conflict("this agent has no root aim")),
+    };
+    if !root_aims.contains(&root_aim) {
+        return Err(AimsError::invalid_input("the survivor has to be one of the current root aims"));
+    }
+
+    let mut removed_roots = Vec::new();
+    let mut moved_connections = 0;
+    for other in root_aims.into_iter().filter(|address| *address != root_aim) {
+        unlink_root_aim(&other)?;
+        moved_connections += move_contributors(&other, &root_aim)?;
+        removed_roots.push(other);
+    }
+    // repeated `has_root_aim` links to the survivor itself collapse into one
+    hdk::remove_link(&hdk::AGENT_ADDRESS, &root_aim, "has_root_aim", "")?;
+    hdk::link_entries(&hdk::AGENT_ADDRESS, &root_aim, "has_root_aim", "")?;
+
+    Ok(RootRepair {
+        root_aim,
+        removed_roots,
+        moved_connections,
+    })
+}
+
+/// The root aims an agent's own source chain links it to: every `has_root_aim`
+/// link it added and did not remove again. Validators all see the same chain,
+/// while the links on the DHT may not have reached all of them yet.
+fn chain_root_aims(agent: &Address, chain_entries: &Option<Vec<Entry>>) -> Result<Vec<Address>, String> {
+    let chain_entries = chain_entries.as_ref()
+        .ok_or_else(|| String::from("the validation package lacks the source chain"))?;
+    let is_root_aim_link = |base: &Address, link_type: &str| base == agent && link_type == "has_root_aim";
+    let mut added = Vec::new();
+    let mut removed = HashSet::new();
+    for entry in chain_entries {
+        match entry {
+            Entry::LinkAdd(link_data) if is_root_aim_link(link_data.link().base(), link_data.link().link_type()) => {
+                added.push((entry.address(), link_data.link().target().clone()));
+            },
+            Entry::LinkRemove((link_data, removed_links)) if is_root_aim_link(link_data.link().base(), link_data.link().link_type()) => {
+                removed.extend(removed_links.iter().cloned());
+            },
+            _ => {},
+        }
+    }
+    Ok(added.into_iter()
+        .filter(|(link_address, _)| !removed.contains(link_address))
+        .map(|(_, root_aim)| root_aim)
+        .collect())
+}
+
+/// Agents can only set their own root aim, and only while they have no other one.
+/// Replacing a root aim therefore removes the old link before adding the new one.
+pub fn validate_root_aim_link(
+    agent: &Address,
+    root_aim: &Address,
+    sources: &[Address],
+    chain_entries: &Option<Vec<Entry>>,
+) -> Result<(), String> {
+    if !sources.contains(agent) {
+        return Err("agents can only set their own root aim".into());
+    }
+    if chain_root_aims(agent, chain_entries)?.iter().any(|address| address != root_aim) {
+        return Err("an agent can only have one root aim, replace it instead".into());
+    }
+    Ok(())
+}
+
+/// Only the author of a root aim can list it on the root aims anchor, and only
+/// while it is their root aim.
+pub fn validate_root_aim_registration(
+    root_aim: &Address,
+    sources: &[Address],
+    chain_entries: &Option<Vec<Entry>>,
+) -> Result<(), String> {
+    let author = get_entry_author(root_aim)
+        .map_err(|_| String::from("could not load the author of this root aim"))?;
+    if !sources.contains(&author) {
+        return Err("only the author of a root aim can register it".into());
+    }
+    if chain_root_aims(&author, chain_entries)?.contains(root_aim) {
+        Ok(())
+    } else {
+        Err("only the current root aim of an agent can be registered".into())
+    }
+}
+
+/// A root aim leaves the root aims anchor when its author replaces it, or when it is
+/// deleted. The shared root aim belonged to everybody, so every agent migrating
+/// away from it may take it off the anchor.
+pub fn validate_root_aim_unregistration(root_aim: &Address, sources: &[Address]) -> Result<(), String> {
+    let shared = shared_root_aim_addresses()
+        .map_err(|_| String::from("could not compute the address of the shared root aim"))?;
+    if shared.contains(root_aim) {
+        return Ok(());
+    }
+    validate_aim_link_removal(root_aim, sources)
+}
+
+/// A root aim belongs to the agent that committed it, and stays theirs when updated.
+pub fn validate_root_of(old_aim: Option<&Aim>, new_aim: &Aim, sources: &[Address]) -> Result<(), String> {
+    match old_aim {
+        Some(old_aim) if old_aim.root_of != new_aim.root_of => {
+            Err("the agent of a root aim cannot be changed".into())
+        },
+        None => match &new_aim.root_of {
+            Some(agent) if !sources.contains(agent) => Err("agents can only create their own root aim".into()),
+            _ => Ok(()),
+        },
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,196 @@
+
+use hdk::holochain_core_types::entry::Entry;
+
+use hdk::holochain_persistence_api::{
+    cas::content::Address,
+};
+
+use hdk::holochain_json_api::{
+    error::JsonError,
+    json::JsonString,
+};
+
+use std::convert::TryFrom;
+
+use crate::{
+    Connection,
+    consent::load_proposal,
+    errors::AimsResult,
+    get_entry_author,
+    roles::can_maintain,
+};
+
+/// How long to wait for the author of a receiving aim to take a notice. Sending
+/// blocks the call that changed the graph, so this is kept short.
+pub const NOTICE_TIMEOUT_MS: u64 = 2_000;
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct AimSignal {
+    aim_address: Address,
+    /// The address of the new version, for updates.
+    new_address: Option<Address>,
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct ConnectionSignal {
+    connection_address: Address,
+    /// The address of the new version, for updates.
+    new_address: Option<Address>,
+    contributing: Address,
+    receiving: Address,
+    /// The agent who made the change, for notices received from other agents.
+    from: Option<Address>,
+}
+
+/// Sent to the author of a receiving aim when another agent connects to it.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct ConnectionNotice {
+    connection_address: Address,
+}
+
+/// Sent to the author of a receiving aim when another agent proposes a connection.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct ProposalNotice {
+    proposal_address: Address,
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct ProposalSignal {
+    proposal_address: Address,
+    contributing: Address,
+    receiving: Address,
+    contribution: u32,
+    from: Address,
+}
+
+/// Signals are a courtesy to the UI, failing to emit one does not fail the change.
+fn emit(name: &str, payload: JsonString) {
+    let _ = hdk::emit_signal(name, payload);
+}
+
+pub fn signal_aim_created(aim_address: &Address) {
+    emit("aim_created", AimSignal {
+        aim_address: aim_address.clone(),
+        new_address: None,
+    }.into());
+}
+
+pub fn signal_aim_updated(aim_address: &Address, new_address: &Address) {
+    emit("aim_updated", AimSignal {
+        aim_address: aim_address.clone(),
+        new_address: Some(new_address.clone()),
+    }.into());
+}
+
+pub fn signal_aim_deleted(aim_address: &Address) {
+    emit("aim_deleted", AimSignal {
+        aim_address: aim_address.clone(),
+        new_address: None,
+    }.into());
+}
+
+fn connection_signal(
+    connection_address: &Address,
+    new_address: Option<Address>,
+    connection: &Connection,
+    from: Option<Address>,
+) -> JsonString {
+    ConnectionSignal {
+        connection_address: connection_address.clone(),
+        new_address,
+        contributing: connection.contributing.clone(),
+        receiving: connection.receiving.clone(),
+        from,
+    }.into()
+}
+
+pub fn signal_connection_created(connection_address: &Address, connection: &Connection) {
+    emit("connection_created", connection_signal(connection_address, None, connection, None));
+}
+
+pub fn signal_connection_updated(connection_address: &Address, new_address: &Address, connection: &Connection) {
+    emit("connection_updated", connection_signal(connection_address, Some(new_address.clone()), connection, None));
+}
+
+pub fn signal_connection_deleted(connection_address: &Address, connection: &Connection) {
+    emit("connection_deleted", connection_signal(connection_address, None, connection, None));
+}
+
+/// Sends a notice to the author of an aim, if that is somebody else. They might be
+/// offline, so a failed delivery is ignored.
+fn notify_author(aim_address: &Address, notice: JsonString) -> AimsResult<()> {
+    let author = get_entry_author(aim_address)?;
+    if author == *hdk::AGENT_ADDRESS {
+        return Ok(());
+    }
+    let _ = hdk::send(author, notice.to_string(), NOTICE_TIMEOUT_MS.into());
+    Ok(())
+}
+
+/// Lets the author of the receiving aim know about a new connection.
+pub fn notify_receiving_author(connection_address: &Address, connection: &Connection) -> AimsResult<()> {
+    notify_author(&connection.receiving, ConnectionNotice {
+        connection_address: connection_address.clone(),
+    }.into())
+}
+
+/// Lets the author of the receiving aim know that somebody proposed a connection.
+pub fn notify_proposal(proposal_address: &Address, receiving_aim_address: &Address) -> AimsResult<()> {
+    notify_author(receiving_aim_address, ProposalNotice {
+        proposal_address: proposal_address.clone(),
+    }.into())
+}
+
+fn receive_connection_notice(from: &Address, notice: ConnectionNotice) -> Result<(), String> {
+    let connection = match hdk::get_entry(&notice.connection_address) {
+        Ok(Some(Entry::App(_, json_string))) => Connection::try_from(json_string)
+            .map_err(|_| String::from("the notice does not refer to a connection"))?,
+        _ => return Err("could not find the connection of the notice".into()),
+    };
+    if get_entry_author(&notice.connection_address).ok().as_ref() != Some(from) {
+        return Err("the notice has to come from the author of the connection".into());
+    }
+    if !can_maintain(&connection.receiving, &hdk::AGENT_ADDRESS).unwrap_or(false) {
+        return Err("the connection does not point to one of our aims".into());
+    }
+    emit("connection_received", connection_signal(&notice.connection_address, None, &connection, Some(from.clone())));
+    Ok(())
+}
+
+fn receive_proposal_notice(from: &Address, notice: ProposalNotice) -> Result<(), String> {
+    let proposal = load_proposal(&notice.proposal_address)
+        .map_err(|_| String::from("could not find the proposal of the notice"))?;
+    if proposal.proposer != *from || get_entry_author(&notice.proposal_address).ok().as_ref() != Some(from) {
+        return Err("the notice has to come from the author of the proposal".into());
+    }
+    if !can_maintain(&proposal.receiving, &hdk::AGENT_ADDRESS).unwrap_or(false) {
+        return Err("the proposal does not point to one of our aims".into());
+    }
+    emit("connection_proposed", ProposalSignal {
+        proposal_address: notice.proposal_address,
+        contributing: proposal.contributing,
+        receiving: proposal.receiving,
+        contribution: proposal.contribution,
+        from: from.clone(),
+    }.into());
+    Ok(())
+}
+
+fn receive_notice(from: &Address, message: String) -> Result<(), String> {
+    let message = JsonString::from_json(&message);
+    if let Ok(notice) = ConnectionNotice::try_from(message.clone()) {
+        return receive_connection_notice(from, notice);
+    }
+    if let Ok(notice) = ProposalNotice::try_from(message) {
+        return receive_proposal_notice(from, notice);
+    }
+    Err("unknown message".into())
+}
+
+/// Handles direct messages from other agents and passes valid ones on to the UI.
+pub fn handle_receive(from: Address, message: String) -> String {
+    match receive_notice(&from, message) {
+        Ok(()) => String::from("ok"),
+        Err(error) => error,
+    }
+}
@@ -0,0 +1,68 @@
+
+use aim_graph::rules::validate_status_transition;
+
+use hdk::holochain_core_types::entry::Entry;
+
+use hdk::holochain_persistence_api::{
+    cas::content::Address,
+};
+
+use hdk::holochain_json_api::{
+    error::JsonError,
+    json::JsonString,
+};
+
+use crate::{
+    Aim,
+    errors::AimsResult,
+    get_aim,
+    signals::signal_aim_updated,
+};
+
+pub use aim_graph::AimStatus;
+
+/// Who set the current status of an aim and when.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+pub struct StatusChange {
+    pub agent: Address,
+    pub timestamp_ms: i64,
+}
+
+/// Sets status and progress of an aim as a new version of it. Achieving an aim sets
+/// its progress to 100%, reopening it keeps the progress unless a new one is given.
+pub fn handle_set_aim_status(
+    aim_address: Address,
+    status: AimStatus,
+    progress: Option<u8>,
+    timestamp_ms: i64,
+) -> AimsResult<Address> {
+    let old_aim = get_aim(&aim_address)?;
+    let progress = match status {
+        AimStatus::Achieved => 100,
+        _ => progress.unwrap_or(old_aim.progress),
+    };
+    let aim = Aim {
+        status,
+        progress,
+        status_change: Some(StatusChange {
+            agent: hdk::AGENT_ADDRESS.clone(),
+            timestamp_ms,
+        }),
+        ..old_aim
+    };
+    let entry = Entry::App("aim".into(), aim.into());
+    let address = hdk::update_entry(entry, &aim_address)?;
+    signal_aim_updated(&aim_address, &address);
+    Ok(address)
+}
+
+pub fn validate_status_change(old_aim: &Aim, new_aim: &Aim, sources: &[Address]) -> Result<(), String> {
+    if old_aim.status == new_aim.status && old_aim.progress == new_aim.progress {
+        return Ok(());
+    }
+    validate_status_transition(&old_aim.to_graph(), &new_aim.to_graph())?;
+    match &new_aim.status_change {
+        Some(change) if sources.contains(&change.agent) && Some(change) != old_aim.status_change.as_ref() => Ok(()),
+        _ => Err("a status change has to record the agent making it".into()),
+    }
+}
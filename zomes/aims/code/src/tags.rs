@@ -0,0 +1,183 @@
+use std::collections::HashSet;
+
+use hdk::{
+    prelude::LinkMatch,
+};
+
+use hdk::holochain_persistence_api::{
+    cas::content::Address,
+};
+
+use hdk::holochain_json_api::{
+    error::JsonError,
+    json::JsonString,
+};
+
+use crate::{
+    anchor_address,
+    commit_anchor,
+    deletion::{
+        is_archived,
+        validate_aim_link_removal,
+    },
+    errors::AimsResult,
+    roles::can_maintain,
+};
+
+/// Whether a tag query covers only the aims of this agent or the aims of everybody.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TagScope {
+    Mine,
+    Global,
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TagMatch {
+    All,
+    Any,
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct TagCount {
+    tag: String,
+    count: u32,
+}
+
+/// Tags are indexed case-insensitively and without a leading '#'.
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim().trim_start_matches('#').trim().to_lowercase()
+}
+
+const TAGS_ANCHOR: &str = "tags";
+
+fn tag_anchor_name(tag: &str) -> String {
+    format!("tag:{}", tag)
+}
+
+fn tags_anchor_address() -> AimsResult<Address> {
+    anchor_address(TAGS_ANCHOR)
+}
+
+fn tag_anchor_address(tag: &str) -> AimsResult<Address> {
+    anchor_address(&tag_anchor_name(tag))
+}
+
+fn normalized_tags(tags: &[String]) -> HashSet<String> {
+    tags.iter()
+        .map(|tag| normalize_tag(tag))
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+pub fn link_tags(aim_address: &Address, tags: &[String]) -> AimsResult<()> {
+    for tag in normalized_tags(tags) {
+        let tag_anchor = commit_anchor(&tag_anchor_name(&tag))?;
+        hdk::link_entries(&commit_anchor(TAGS_ANCHOR)?, &tag_anchor, "has_tag", tag.as_str())?;
+        hdk::link_entries(&tag_anchor, aim_address, "tagged_aim", "")?;
+    }
+    Ok(())
+}
+
+pub fn unlink_tags(aim_address: &Address, tags: &[String]) -> AimsResult<()> {
+    for tag in normalized_tags(tags) {
+        hdk::remove_link(&tag_anchor_address(&tag)?, aim_address, "tagged_aim", "")?;
+    }
+    Ok(())
+}
+
+/// Tags change with the aim, so maintainers can take an aim out of the tag index.
+/// Otherwise it is removed only along with the aim.
+pub fn validate_tag_link_removal(aim_address: &Address, sources: &[Address]) -> Result<(), String> {
+    if sources.iter().any(|source| can_maintain(aim_address, source).unwrap_or(false)) {
+        return Ok(());
+    }
+    validate_aim_link_removal(aim_address, sources)
+}
+
+/// Brings the tag index in line with an updated aim: links for removed tags are
+/// removed, links for new tags are added.
+pub fn relink_tags(aim_address: &Address, old_tags: &[String], new_tags: &[String]) -> AimsResult<()> {
+    let old_tags = normalized_tags(old_tags);
+    let new_tags = normalized_tags(new_tags);
+    for removed in old_tags.difference(&new_tags) {
+        hdk::remove_link(&tag_anchor_address(removed)?, aim_address, "tagged_aim", "")?;
+    }
+    let added: Vec<String> = new_tags.difference(&old_tags).cloned().collect();
+    link_tags(aim_address, &added)
+}
+
+fn own_aim_addresses() -> AimsResult<HashSet<Address>> {
+    Ok(hdk::get_links(
+        &hdk::AGENT_ADDRESS,
+        LinkMatch::Exactly("created_aim"),
+        LinkMatch::Any
+    )?.addresses().into_iter().collect())
+}
+
+fn tagged_aim_addresses(tag: &str, scope: &TagScope) -> AimsResult<Vec<Address>> {
+    let addresses = hdk::get_links(
+        &tag_anchor_address(&normalize_tag(tag))?,
+        LinkMatch::Exactly("tagged_aim"),
+        LinkMatch::Any
+    )?.addresses();
+    let own_aims = match scope {
+        TagScope::Mine => Some(own_aim_addresses()?),
+        TagScope::Global => None,
+    };
+    let mut result = Vec::new();
+    for address in addresses {
+        if own_aims.as_ref().map_or(true, |own| own.contains(&address))
+            && !result.contains(&address)
+            && !is_archived(&address)? {
+            result.push(address);
+        }
+    }
+    Ok(result)
+}
+
+pub fn handle_get_aims_by_tag(tag: String, scope: TagScope) -> AimsResult<Vec<Address>> {
+    tagged_aim_addresses(&tag, &scope)
+}
+
+pub fn handle_get_aims_by_tags(tags: Vec<String>, tag_match: TagMatch, scope: TagScope) -> AimsResult<Vec<Address>> {
+    let mut result: Option<Vec<Address>> = None;
+    for tag in tags {
+        let tagged = tagged_aim_addresses(&tag, &scope)?;
+        result = Some(match (result, &tag_match) {
+            (None, _) => tagged,
+            (Some(so_far), TagMatch::All) => so_far.into_iter()
+                .filter(|address| tagged.contains(address))
+                .collect(),
+            (Some(mut so_far), TagMatch::Any) => {
+                for address in tagged {
+                    if !so_far.contains(&address) {
+                        so_far.push(address);
+                    }
+                }
+                so_far
+            }
+        });
+    }
+    Ok(result.unwrap_or_default())
+}
+
+pub fn handle_list_tags_with_counts(scope: TagScope) -> AimsResult<Vec<TagCount>> {
+    let mut tags: Vec<String> = hdk::get_links(
+        &tags_anchor_address()?,
+        LinkMatch::Exactly("has_tag"),
+        LinkMatch::Any
+    )?.tags();
+    tags.sort();
+    tags.dedup();
+    let mut counts = Vec::new();
+    for tag in tags {
+        let count = tagged_aim_addresses(&tag, &scope)?.len() as u32;
+        if count > 0 {
+            counts.push(TagCount { tag, count });
+        }
+    }
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tag.cmp(&b.tag)));
+    Ok(counts)
+}
@@ -0,0 +1,119 @@
+use std::collections::{HashSet, VecDeque};
+
+use hdk::{
+    prelude::LinkMatch,
+};
+
+use hdk::holochain_persistence_api::{
+    cas::content::Address,
+};
+
+use hdk::holochain_json_api::{
+    error::JsonError,
+    json::JsonString,
+};
+
+use crate::{
+    Aim,
+    ConnectedAim,
+    deletion::is_archived,
+    errors::{
+        AddressError,
+        AimsResult,
+    },
+    get_aim,
+    graph::relation,
+    load_connected_aims,
+};
+
+pub use aim_graph::Direction;
+
+/// One aim of the traversal together with its directly connected aims. Every aim
+/// shows up as a node only once, even if several paths lead to it.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct AimTreeNode {
+    pub aim_address: Address,
+    pub depth: u32,
+    /// Set if the aim has connections to aims missing from the tree, which were not
+    /// followed because the depth limit was reached. Archived aims do not count.
+    pub truncated: bool,
+    pub connected_aims: Vec<ConnectedAim>,
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct AimTree {
+    pub root_address: Address,
+    pub root_aim: Aim,
+    pub direction: Direction,
+    pub nodes: Vec<AimTreeNode>,
+    /// Connections that could not be followed, the walk went on without them.
+    pub errors: Vec<AddressError>,
+}
+
+/// Whether a connection of the aim leads to an aim that is neither archived nor
+/// already part of the tree. Only links are read, not the connected aims.
+fn has_unvisited_connections(aim_address: &Address, direction: &Direction, seen: &HashSet<Address>) -> AimsResult<bool> {
+    let relation = relation(direction);
+    for connection_address in hdk::get_links(
+        aim_address,
+        LinkMatch::Exactly(&format!("{}_connection", relation)),
+        LinkMatch::Any
+    )?.addresses() {
+        for connected_address in hdk::get_links(
+            &connection_address,
+            LinkMatch::Exactly(&format!("{}_aim", relation)),
+            LinkMatch::Any
+        )?.addresses() {
+            if !seen.contains(&connected_address) && !is_archived(&connected_address)? {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Walks the contribution graph breadth first from the given aim, up to
+/// `max_depth` connections away, and returns it as an adjacency list.
+pub fn handle_get_aim_tree(aim_address: Address, direction: Direction, max_depth: u32) -> AimsResult<AimTree> {
+    let root_aim = get_aim(&aim_address)?;
+    let mut nodes = Vec::new();
+    let mut errors = Vec::new();
+    let mut seen: HashSet<Address> = HashSet::new();
+    let mut queue: VecDeque<(Address, u32)> = VecDeque::new();
+    seen.insert(aim_address.clone());
+    queue.push_back((aim_address.clone(), 0));
+
+    while let Some((current, depth)) = queue.pop_front() {
+        if depth >= max_depth {
+            nodes.push(AimTreeNode {
+                truncated: has_unvisited_connections(&current, &direction, &seen)?,
+                aim_address: current,
+                depth,
+                connected_aims: Vec::new(),
+            });
+            continue;
+        }
+        let loaded = load_connected_aims(&current, relation(&direction))?;
+        errors.extend(loaded.errors);
+        let connected_aims = loaded.aims;
+        for connected in connected_aims.iter() {
+            if seen.insert(connected.aim_address.clone()) {
+                queue.push_back((connected.aim_address.clone(), depth + 1));
+            }
+        }
+        nodes.push(AimTreeNode {
+            aim_address: current,
+            depth,
+            truncated: false,
+            connected_aims,
+        });
+    }
+
+    Ok(AimTree {
+        root_address: aim_address,
+        root_aim,
+        direction,
+        nodes,
+        errors,
+    })
+}
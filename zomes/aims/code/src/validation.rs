@@ -0,0 +1,55 @@
+use aim_graph::rules::{
+    validate_aim,
+    validate_connection,
+    validate_timestamp,
+};
+
+use chrono::DateTime;
+
+use hdk::holochain_core_types::{
+    chain_header::ChainHeader,
+    entry::Entry,
+};
+
+use hdk::holochain_persistence_api::{
+    cas::content::Address,
+};
+
+use crate::{
+    Aim,
+    Connection,
+    properties::effort_scale,
+};
+
+pub fn validate_aim_content(aim: &Aim, header: &ChainHeader) -> Result<(), String> {
+    validate_aim(&aim.to_graph(), &effort_scale())?;
+    let header_ms = DateTime::parse_from_rfc3339(&header.timestamp().to_string())
+        .map_err(|_| String::from("could not read the header timestamp"))?
+        .timestamp_millis();
+    validate_timestamp(aim.timestamp_ms, header_ms)
+}
+
+pub fn validate_connection_content(connection: &Connection) -> Result<(), String> {
+    validate_connection(&connection.to_graph())?;
+    validate_is_aim(&connection.contributing)?;
+    validate_is_aim(&connection.receiving)
+}
+
+fn validate_is_aim(address: &Address) -> Result<(), String> {
+    match hdk::get_entry(address) {
+        Ok(Some(Entry::App(entry_type, _))) if String::from(entry_type) == "aim" => Ok(()),
+        Ok(_) => Err(format!("{} is not an aim", address)),
+        Err(_) => Err(format!("could not load {}", address)),
+    }
+}
+
+/// Entries may only be changed by the agent who authored them originally.
+pub fn validate_author(old_entry_header: &ChainHeader, sources: &[Address]) -> Result<(), String> {
+    let is_author = old_entry_header.provenances().iter()
+        .any(|provenance| sources.contains(&provenance.source()));
+    if is_author {
+        Ok(())
+    } else {
+        Err("only the author may change this entry".into())
+    }
+}
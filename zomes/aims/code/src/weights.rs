@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+
+use hdk::holochain_core_types::entry::Entry;
+
+use hdk::holochain_persistence_api::{
+    cas::content::Address,
+};
+
+use hdk::holochain_json_api::{
+    error::JsonError,
+    json::JsonString,
+};
+
+use crate::{
+    Connection,
+    errors::{
+        AimsError,
+        AimsResult,
+        load_app_entry,
+    },
+    get_connected_aims,
+    roles::can_maintain,
+    signals::signal_connection_updated,
+};
+
+/// What the contributions of all inbound connections of an aim add up to after
+/// `set_contribution_weights` normalized them.
+pub const CONTRIBUTION_TOTAL: u32 = 1000;
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct ContributionWeight {
+    contributing_aim: Address,
+    connection_address: Address,
+    contribution: u32,
+}
+
+fn load_connection(connection_address: &Address) -> AimsResult<Connection> {
+    load_app_entry(connection_address, "connection")
+}
+
+/// Moves the four links of a connection from its previous version to the new one,
+/// so traversals and deletion keep working on the address the links point to.
+/// Comments stay linked to the first version, see `comments::comment_target`.
+pub fn relink_connection(old_address: &Address, new_address: &Address, connection: &Connection) -> AimsResult<()> {
+    hdk::remove_link(&connection.contributing, old_address, "contributes_to_connection", "")?;
+    hdk::remove_link(old_address, &connection.receiving, "contributes_to_aim", "")?;
+    hdk::remove_link(&connection.receiving, old_address, "receives_from_connection", "")?;
+    hdk::remove_link(old_address, &connection.contributing, "receives_from_aim", "")?;
+
+    hdk::link_entries(&connection.contributing, new_address, "contributes_to_connection", "")?;
+    hdk::link_entries(new_address, &connection.receiving, "contributes_to_aim", "")?;
+    hdk::link_entries(&connection.receiving, new_address, "receives_from_connection", "")?;
+    hdk::link_entries(new_address, &connection.contributing, "receives_from_aim", "")?;
+    Ok(())
+}
+
+/// Changes how much a connection contributes. Only owners and maintainers of the
+/// receiving aim can reweight its inbound connections.
+pub fn handle_update_connection_contribution(connection_address: Address, contribution: u32) -> AimsResult<Address> {
+    if contribution == 0 {
+        return Err(AimsError::invalid_input("the contribution must be greater than zero, delete the connection instead"));
+    }
+    let old_connection = load_connection(&connection_address)?;
+    if !can_maintain(&old_connection.receiving, &hdk::AGENT_ADDRESS)? {
+        return Err(AimsError::unauthorized("only owners and maintainers of the receiving aim can reweight its connections"));
+    }
+    if old_connection.contribution == contribution {
+        return Ok(connection_address);
+    }
+    let connection = Connection {
+        contribution,
+        ..old_connection
+    };
+    let entry = Entry::App("connection".into(), connection.clone().into());
+    let new_address = hdk::update_entry(entry, &connection_address)?;
+    relink_connection(&connection_address, &new_address, &connection)?;
+    signal_connection_updated(&connection_address, &new_address, &connection);
+    Ok(new_address)
+}
+
+/// Rebalances all inbound connections of an aim. The weights are relative and are
+/// scaled so the contributions add up to `CONTRIBUTION_TOTAL`. Every contributing
+/// aim needs a weight; connections from the same aim share its weight equally.
+pub fn handle_set_contribution_weights(
+    receiving_aim_address: Address,
+    weights: HashMap<Address, f64>,
+) -> AimsResult<Vec<ContributionWeight>> {
+    let contributors = get_connected_aims(receiving_aim_address, "receives_from".into())?;
+    let mut connection_counts: HashMap<Address, u32> = HashMap::new();
+    for connected in contributors.iter() {
+        *connection_counts.entry(connected.aim_address.clone()).or_insert(0) += 1;
+    }
+    for (contributing_aim, weight) in weights.iter() {
+        if !connection_counts.contains_key(contributing_aim) {
+            return Err(AimsError::invalid_input(format!("{} does not contribute to this aim", contributing_aim)));
+        }
+        if !weight.is_finite() || *weight <= 0.0 {
+            return Err(AimsError::invalid_input("weights have to be greater than zero"));
+        }
+    }
+    if let Some(missing) = connection_counts.keys().find(|aim_address| !weights.contains_key(aim_address)) {
+        return Err(AimsError::invalid_input(format!("no weight given for contributing aim {}", missing)));
+    }
+
+    let weight_sum: f64 = weights.values().sum();
+    let mut result = Vec::new();
+    for connected in contributors {
+        let share = weights[&connected.aim_address] / weight_sum / connection_counts[&connected.aim_address] as f64;
+        let contribution = ((share * CONTRIBUTION_TOTAL as f64).round() as u32).max(1);
+        result.push(ContributionWeight {
+            connection_address: handle_update_connection_contribution(connected.connection_address, contribution)?,
+            contributing_aim: connected.aim_address,
+            contribution,
+        });
+    }
+    Ok(result)
+}
+
+/// Like `update_connection_contribution`, only owners and maintainers of the
+/// receiving aim may reweight a connection, and it cannot be moved to other aims.
+pub fn validate_connection_update(
+    old_connection: &Connection,
+    new_connection: &Connection,
+    sources: &[Address],
+) -> Result<(), String> {
+    if old_connection.contributing != new_connection.contributing || old_connection.receiving != new_connection.receiving {
+        return Err("a connection cannot be moved to other aims, create a new one instead".into());
+    }
+    let maintains_receiving = sources.iter()
+        .any(|source| can_maintain(&new_connection.receiving, source).unwrap_or(false));
+    if maintains_receiving {
+        Ok(())
+    } else {
+        Err("only owners and maintainers of the receiving aim may change this connection".into())
+    }
+}
@@ -4,18 +4,15 @@ extern crate serde;
 #[macro_use]
 extern crate serde_derive;
 extern crate serde_json;
+extern crate entries;
 #[macro_use]
 extern crate holochain_json_derive;
 
 use hdk::{
     error::ZomeApiResult,
-	prelude::{
-		LinkMatch, 
-		EntryType
-	},
+	prelude::EntryType,
 	holochain_persistence_api::{
 		cas::content::Address,
-        hash::HashString,
 	},
 	holochain_core_types::{
 		entry::{
@@ -29,7 +26,12 @@ use hdk::{
 	}
 };
 
-use std::convert::TryFrom; 
+use entries::{
+    AddressAndEntry,
+    LoadFailure,
+    load_linked,
+    load_linked_entries,
+};
 
 // see https://developer.holochain.org/api/0.0.47-alpha1/hdk/ for info on using the hdk library
 
@@ -42,13 +44,14 @@ pub struct Profile{
 	creator: Address
 }
 
-#[derive(Serialize, Deserialize, Debug, DefaultJson,Clone)]
-pub struct AddressAndEntry{ //TODO make this generic, not only for Profile
-    entry: Profile, 
-    address: HashString
+/// The profiles of an agent, with the ones that could not be loaded listed in
+/// `errors`.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct ProfileList {
+    profiles: Vec<AddressAndEntry<Profile>>,
+    errors: Vec<LoadFailure>,
 }
 
-
 pub fn handle_get_my_agent_address() -> ZomeApiResult<Address> {
 	Ok(hdk::AGENT_ADDRESS.clone())
 }
@@ -69,48 +72,23 @@ pub fn handle_create_profile(name: String) -> ZomeApiResult<Address> {
     Ok(address)
 }
 
-pub fn handle_get_my_profiles() -> ZomeApiResult<Vec<AddressAndEntry>> {
-    Ok( hdk::get_links(
-		&hdk::AGENT_ADDRESS.clone(), 
-		LinkMatch::Exactly("agent_created_profile"), 
-		LinkMatch::Any
-	)?.addresses().iter()
-        .filter_map::<AddressAndEntry,_>(|address| {
-            match hdk::api::get_entry(&address) {
-                Ok(option) => {
-                    return match option {
-                        Some(entry) => {
-                            match entry {
-                                Entry::App(_, entry_value) => {
-                                    return match Profile::try_from(entry_value.to_owned()) {
-                                        Ok(profile) => Some(
-                                            AddressAndEntry {
-                                                address: address.clone(),
-                                                entry: profile
-                                            }
-                                        ), 
-                                        Err(_) => None
-                                    }
-                                },
-                                _ => None,
-                            }
-                        },
-                        None => None,
-                    }
-                }, 
-                Err(_) => None,
-            }
-        })
-        .collect::<Vec<AddressAndEntry>>()
-    )
+pub fn handle_get_my_profiles() -> ZomeApiResult<ProfileList> {
+    handle_get_agent_profiles(hdk::AGENT_ADDRESS.clone())
+}
+
+pub fn handle_get_agent_profiles(agent_address: Address) -> ZomeApiResult<ProfileList> {
+    let loaded = load_linked_entries::<Profile>(&agent_address, "agent_created_profile")?;
+    Ok(ProfileList {
+        profiles: loaded.entries,
+        errors: loaded.failures,
+    })
 }
 
 pub fn handle_get_my_profiles_without_addresses() -> ZomeApiResult<Vec<Profile>> {
-    hdk::utils::get_links_and_load_type(
-        &hdk::AGENT_ADDRESS.clone(), 
-        LinkMatch::Exactly("agent_created_profile"),
-        LinkMatch::Any
-    )
+    Ok(load_linked::<Profile>(&hdk::AGENT_ADDRESS, "agent_created_profile")?
+        .into_iter()
+        .map(|profile| profile.entry)
+        .collect())
 }
 
 define_zome! {
@@ -159,16 +137,22 @@ define_zome! {
         }
 		get_my_profiles: {
 			inputs: | |, 
-			outputs: |result: ZomeApiResult<Vec<AddressAndEntry>>|, 
+			outputs: |result: ZomeApiResult<ProfileList>|, 
 			handler: handle_get_my_profiles
 		}
+		get_agent_profiles: {
+			inputs: |agent_address: Address|, 
+			outputs: |result: ZomeApiResult<ProfileList>|, 
+			handler: handle_get_agent_profiles
+		}
     ]
 
     traits: {
         hc_public [
 			get_my_agent_address, 
 			create_profile,
-			get_my_profiles
+			get_my_profiles,
+			get_agent_profiles
 		]
     }
 }